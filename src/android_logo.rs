@@ -1,55 +1,37 @@
-pub fn run() -> String {
-  // Define ANSI color codes
-  const GREEN: &[u8] = b"\x1B[1;32m";
-  const WHITE: &[u8] = b"\x1B[39m";
-  const RESET: &[u8] = b"\x1B[0m";
-
-  // Define logo sections
-  const TOP: &[u8] = b"             -o          o-
-              +hydNNNNdyh+
-            +mMMMMMMMMMMMMm+
-          `dMM";
-
-  const MIDDLE: &[u8] = b"MMd`
-          hMMMMMMMMMMMMMMMMMMh
-      ..  yyyyyyyyyyyyyyyyyyyy  ..
-    .mMMm`MMMMMMMMMMMMMMMMMMMM`mMMm.";
-
-  const BODY: &[u8] = b"
-    :MMMM-MMMMMMMMMMMMMMMMMMMM-MMMM:
-    :MMMM-MMMMMMMMMMMMMMMMMMMM-MMMM:
-    :MMMM-MMMMMMMMMMMMMMMMMMMM-MMMM:
-    :MMMM-MMMMMMMMMMMMMMMMMMMM-MMMM:
-    -MMMM-MMMMMMMMMMMMMMMMMMMM-MMMM-";
-
-  const BOTTOM: &[u8] = b"
-     +yy+ MMMMMMMMMMMMMMMMMMMM +yy+
-          mMMMMMMMMMMMMMMMMMMm
-          `/++MMMMh++hMMMM++/`
-              MMMMo  oMMMM
-              MMMMo  oMMMM
-              oNMm-  -mMNs";
-
-  // Build output string
-  let mut output = Vec::new();
-
-  output.extend_from_slice(GREEN);
-  output.extend_from_slice(TOP);
-
-  // Eyes with white colons
-  output.extend_from_slice(WHITE);
-  output.extend_from_slice(b"m:");
-  output.extend_from_slice(GREEN);
-  output.extend_from_slice(b"NMMMMMMN");
-  output.extend_from_slice(WHITE);
-  output.extend_from_slice(b":m");
-
-  // Rest of logo in green
-  output.extend_from_slice(GREEN);
-  output.extend_from_slice(MIDDLE);
-  output.extend_from_slice(BODY);
-  output.extend_from_slice(BOTTOM);
-  output.extend_from_slice(RESET);
-
-  String::from_utf8_lossy(&output).to_string()
+use crate::logo;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+
+pub struct AndroidLogo;
+
+impl Module for AndroidLogo {
+  fn name(&self) -> &'static str {
+    "android-logo"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('L')
+  }
+
+  fn category(&self) -> Category {
+    Category::Branding
+  }
+
+  fn description(&self) -> &'static str {
+    "Large distro logo (Android robot on Android)"
+  }
+
+  fn is_logo(&self) -> bool {
+    true
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    let distro = logo::distro(&ctx.options)?;
+    Ok(Output::text(logo::render(
+      distro.large,
+      &logo::palette(&ctx.options, distro.colors)?,
+    )))
+  }
 }
@@ -1,26 +1,37 @@
-pub fn run() -> String {
-  // Define ANSI color codes
-  const GREEN: &str = "\x1b[1;32m";
-  const WHITE: &str = "\x1b[39m";
-  const RESET: &str = "\x1b[0m";
+use crate::logo;
+use crate::module::{Category, Context, Module, ModuleError, Output};
 
-  // Build the ASCII art with color codes
-  let mut output = String::new();
+pub struct AndroidLogoSmall;
 
-  // First part in green
-  output.push_str(GREEN);
-  output.push_str("  ;,           ,;\n   ';,.-----.,;'\n  ,'           ',\n /    ");
+impl Module for AndroidLogoSmall {
+  fn name(&self) -> &'static str {
+    "android-logo-small"
+  }
 
-  // Eyes in white
-  output.push_str(WHITE);
-  output.push_str("O     O");
+  fn flag(&self) -> Option<char> {
+    Some('l')
+  }
 
-  // Back to green for the rest
-  output.push_str(GREEN);
-  output.push_str("    \\\n|                 |\n'-----------------'\n");
+  fn category(&self) -> Category {
+    Category::Branding
+  }
 
-  // Reset colors
-  output.push_str(RESET);
+  fn description(&self) -> &'static str {
+    "Small distro logo (Android robot on Android)"
+  }
 
-  output
+  fn is_logo(&self) -> bool {
+    true
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    let distro = logo::distro(&ctx.options)?;
+    Ok(Output::text(logo::render(
+      distro.small,
+      &logo::palette(&ctx.options, distro.colors)?,
+    )))
+  }
 }
@@ -1,43 +1,179 @@
-use std::fs;
-
-pub fn run() -> String {
-  // ANSI color codes
-  const BOLD: &str = "\x1B[1m";
-  const RED: &str = "\x1B[1;31m";
-  const GREEN: &str = "\x1B[1;32m";
-  const YELLOW: &str = "\x1B[1;33m";
-  const NC: &str = "\x1B[0m";
-
-  // Read temperature file
-  let cpu_temp = match fs::read_to_string("/sys/class/thermal/thermal_zone0/temp") {
-    Ok(temp) => temp,
-    Err(_) => {
-      return format!(
-        "{}Temperature:{} {}Error reading temperature{}",
-        BOLD, NC, RED, NC
-      );
+use serde::{Deserialize, Serialize};
+
+use crate::glob::matches_any;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::sensors::{self, Backend, Sensor};
+use crate::style;
+use crate::temperature::{self, Thresholds};
+
+pub struct AndroidTemp;
+
+// Sensors that usually measure the CPU/SoC, used when no zones are configured
+const CPU_ZONE_PATTERNS: &[&str] = &[
+  "*cpu*",
+  "*soc*",
+  "*tsens*",
+  "x86_pkg_temp",
+  "coretemp*",
+  "k10temp*",
+  "zenpower*",
+  "acpitz",
+];
+
+// Thresholds for sensors whose driver reports none
+const DEFAULT_THRESHOLDS: Thresholds = Thresholds::celsius(60, 75);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Aggregate {
+  // One line per zone
+  None,
+  #[default]
+  Max,
+  Avg,
+}
+
+#[derive(Serialize)]
+struct Temperatures<'a> {
+  readings: Vec<&'a Sensor>,
+  aggregate: Aggregate,
+  // Aggregated value, absent when showing every zone
+  millidegrees: Option<i64>,
+}
+
+impl Module for AndroidTemp {
+  fn name(&self) -> &'static str {
+    "android-temp"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('t')
+  }
+
+  fn category(&self) -> Category {
+    Category::Temperature
+  }
+
+  fn description(&self) -> &'static str {
+    "Temperature sensors (thermal zones and hwmon)"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  // Zones are picked by glob on their label ("cpu-0-0-usr", "coretemp Core 0") or source
+  // ("thermal_zone3", "hwmon1/temp2"). Without a configuration the CPU-like sensors are used.
+  let zones: Option<Vec<String>> = ctx.options.get("zones");
+  let aggregate: Aggregate = ctx.options.get("aggregate").unwrap_or_default();
+  let backends: Vec<Backend> = ctx
+    .options
+    .get("backends")
+    .unwrap_or_else(|| vec![Backend::Thermal, Backend::Hwmon]);
+
+  let all_readings: Vec<Sensor> = backends.into_iter().flat_map(sensors::read).collect();
+  if all_readings.is_empty() {
+    return Err(ModuleError::Failed("Error reading temperature".to_string()));
+  }
+
+  let matching = |patterns: &[String]| -> Vec<&Sensor> {
+    all_readings
+      .iter()
+      .filter(|reading| {
+        matches_any(patterns, &reading.label) || matches_any(patterns, &reading.source)
+      })
+      .collect()
+  };
+
+  let selected = match &zones {
+    Some(patterns) => matching(patterns),
+    None => {
+      let cpu_patterns: Vec<String> = CPU_ZONE_PATTERNS.iter().map(|p| p.to_string()).collect();
+      let cpu_zones = matching(&cpu_patterns);
+      if cpu_zones.is_empty() {
+        all_readings.iter().collect()
+      } else {
+        cpu_zones
+      }
     }
   };
 
-  let temp_str = cpu_temp.trim();
+  if selected.is_empty() {
+    return Err(ModuleError::Failed(
+      "No thermal zone matches the configured zones".to_string(),
+    ));
+  }
 
-  // Extract first 2 digits (convert millidegrees to degrees)
-  let temp_c = if temp_str.len() >= 3 {
-    &temp_str[0..2]
-  } else {
-    "00"
+  let unit = temperature::unit(ctx);
+  let colored = |millidegrees: i64, sensor: Option<&Sensor>| -> String {
+    let names: Vec<&str> = sensor
+      .map(|sensor| vec![sensor.label.as_str(), sensor.source.as_str()])
+      .unwrap_or_default();
+    let driver = sensor.and_then(|sensor| Thresholds::from_driver(sensor.max, sensor.critical));
+    let thresholds =
+      temperature::thresholds_for(&ctx.options, unit, &names, driver, DEFAULT_THRESHOLDS);
+    temperature::colored_temperature(millidegrees, thresholds, unit)
+  };
+
+  let theme = style::theme();
+  let mut text = String::new();
+
+  let millidegrees = match aggregate {
+    Aggregate::None => {
+      text.push_str(&format!("{}Temperatures:{}\n", theme.bold, theme.reset));
+      let label_width = selected
+        .iter()
+        .map(|reading| reading.label.len())
+        .max()
+        .unwrap_or(0);
+      for reading in &selected {
+        text.push_str(&format!(
+          "  {:<width$}  {}\n",
+          reading.label,
+          colored(reading.millidegrees, Some(reading)),
+          width = label_width
+        ));
+      }
+      None
+    }
+    Aggregate::Max => {
+      let hottest = selected
+        .iter()
+        .max_by_key(|reading| reading.millidegrees)
+        .unwrap();
+      text.push_str(&format!(
+        "{}Temperature:{} {} ({})\n",
+        theme.bold,
+        theme.reset,
+        colored(hottest.millidegrees, Some(hottest)),
+        hottest.label
+      ));
+      Some(hottest.millidegrees)
+    }
+    Aggregate::Avg => {
+      let sum: i64 = selected.iter().map(|reading| reading.millidegrees).sum();
+      let average = sum / selected.len() as i64;
+      text.push_str(&format!(
+        "{}Temperature:{} {} (avg of {} zones)\n",
+        theme.bold,
+        theme.reset,
+        colored(average, None),
+        selected.len()
+      ));
+      Some(average)
+    }
   };
 
-  // Determine color based on temperature
-  let temp_value: u32 = temp_c.parse().unwrap_or(0);
-  let color = if temp_value < 60 {
-    GREEN
-  } else if temp_value <= 75 {
-    YELLOW
-  } else {
-    RED
+  let data = Temperatures {
+    readings: selected,
+    aggregate,
+    millidegrees,
   };
 
-  // Return formatted output
-  format!("{}Temperature:{} {} {}°C{}\n", BOLD, NC, color, temp_c, NC)
+  Ok(Output::new(text, &data))
 }
@@ -0,0 +1,30 @@
+use crate::style;
+
+// Nerd Font progress bar glyphs
+const BAR_START: &str = "\u{ee03}";
+const BAR_FILLED: &str = "\u{ee04}";
+const BAR_EMPTY: &str = "\u{ee01}";
+const BAR_END_FULL: &str = "\u{ee05}";
+const BAR_END_EMPTY: &str = "\u{ee02}";
+
+// A bar `width` cells wide, filled to `percent` and drawn in `color`
+pub fn render(
+  percent: u32,
+  width: usize,
+  color: &str,
+) -> String {
+  let filled = (percent.min(100) as usize * width) / 100;
+
+  let mut bar = String::new();
+  bar.push_str(color);
+  bar.push_str(BAR_START);
+  bar.push_str(&BAR_FILLED.repeat(filled));
+  bar.push_str(&BAR_EMPTY.repeat(width - filled));
+  bar.push_str(if filled == width {
+    BAR_END_FULL
+  } else {
+    BAR_END_EMPTY
+  });
+  bar.push_str(&style::theme().reset);
+  bar
+}
@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::bar;
+use crate::fsutil;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::temperature::{self, Thresholds};
+
+pub struct Battery;
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+// Batteries get warm long before a SoC does; these apply unless overridden in the config
+const DEFAULT_THRESHOLDS: Thresholds = Thresholds::celsius(40, 45);
+
+#[derive(Serialize)]
+struct Batteries {
+  batteries: Vec<BatteryData>,
+}
+
+#[derive(Serialize)]
+struct BatteryData {
+  // power_supply directory name, e.g. "battery" on Android or "BAT0" on laptops
+  name: String,
+  capacity_percent: u32,
+  status: Option<String>,
+  health: Option<String>,
+  millidegrees: Option<i64>,
+  current_microamps: Option<i64>,
+  voltage_microvolts: Option<i64>,
+  seconds_to_full: Option<u64>,
+  seconds_to_empty: Option<u64>,
+}
+
+impl Module for Battery {
+  fn name(&self) -> &'static str {
+    "battery"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('p')
+  }
+
+  fn category(&self) -> Category {
+    Category::Power
+  }
+
+  fn description(&self) -> &'static str {
+    "Battery charge, health and time remaining"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.battery] table)
+  let low_capacity: u32 = options.get("low_capacity").unwrap_or(15);
+  let alert_capacity: u32 = options.get("alert_capacity").unwrap_or(30);
+  let bar_width: usize = options.get("bar_width").unwrap_or(50);
+  let unit = temperature::unit(ctx);
+
+  let batteries = read_batteries();
+  // Nothing to report on desktops and servers
+  if batteries.is_empty() {
+    return Ok(Output::text(String::new()));
+  }
+
+  let theme = style::theme();
+  let mut output = String::new();
+
+  for battery in &batteries {
+    let mut details = vec![format!("{}%", battery.capacity_percent)];
+    details.extend(battery.status.clone());
+    if let Some(health) = &battery.health {
+      details.push(format!("health {}", health.to_lowercase()));
+    }
+    if let Some(millidegrees) = battery.millidegrees {
+      let thresholds =
+        temperature::thresholds_for(options, unit, &[&battery.name], None, DEFAULT_THRESHOLDS);
+      details.push(temperature::colored_temperature(
+        millidegrees,
+        thresholds,
+        unit,
+      ));
+    }
+
+    output.push_str(&format!(
+      "\n{}Battery ({}):{} {}\n",
+      theme.bold,
+      battery.name,
+      theme.reset,
+      details.join(", ")
+    ));
+
+    // Low charge is the bad end, so the colors run opposite to disk usage
+    let color = if battery.capacity_percent <= low_capacity {
+      &theme.critical
+    } else if battery.capacity_percent <= alert_capacity {
+      &theme.warn
+    } else {
+      &theme.ok
+    };
+    output.push_str(&format!(
+      "  {}\n",
+      bar::render(battery.capacity_percent, bar_width, color)
+    ));
+
+    let mut draw = Vec::new();
+    if let Some(current) = battery.current_microamps {
+      draw.push(format!("{:.2} A", current.abs() as f64 / 1_000_000.0));
+    }
+    if let Some(voltage) = battery.voltage_microvolts {
+      draw.push(format!("{:.2} V", voltage as f64 / 1_000_000.0));
+    }
+    let estimate = match (battery.seconds_to_full, battery.seconds_to_empty) {
+      (Some(seconds), _) => Some(format!("{} until full", format_duration(seconds))),
+      (_, Some(seconds)) => Some(format!("{} remaining", format_duration(seconds))),
+      _ => None,
+    };
+    if !draw.is_empty() || estimate.is_some() {
+      let mut line = draw.join(" at ");
+      if let Some(estimate) = estimate {
+        if !line.is_empty() {
+          line.push_str(", ");
+        }
+        line.push_str(&estimate);
+      }
+      output.push_str(&format!("  {}\n", line));
+    }
+  }
+
+  Ok(Output::new(output, &Batteries { batteries }))
+}
+
+// Every power supply of type Battery, skipping the ones that power peripherals (mice, pens)
+fn read_batteries() -> Vec<BatteryData> {
+  let Ok(entries) = fs::read_dir(POWER_SUPPLY_DIR) else {
+    return Vec::new();
+  };
+
+  let mut batteries: Vec<BatteryData> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let path = entry.path();
+      if fsutil::read_trimmed(&path.join("type")).as_deref() != Some("Battery")
+        || fsutil::read_trimmed(&path.join("scope")).as_deref() == Some("Device")
+      {
+        return None;
+      }
+      read_battery(&path, entry.file_name().to_string_lossy().into_owned())
+    })
+    .collect();
+
+  batteries.sort_by(|a, b| a.name.cmp(&b.name));
+  batteries
+}
+
+fn read_battery(
+  path: &Path,
+  name: String,
+) -> Option<BatteryData> {
+  let capacity_percent: u32 = fsutil::read_trimmed(&path.join("capacity"))?.parse().ok()?;
+  let status = fsutil::read_trimmed(&path.join("status"));
+  let number =
+    |attribute: &str| -> Option<i64> { fsutil::read_trimmed(&path.join(attribute))?.parse().ok() };
+
+  // Drivers disagree on the sign of current_now, the status tells the direction
+  let current_microamps = number("current_now").filter(|current| *current != 0);
+  let (seconds_to_full, seconds_to_empty) = match (current_microamps, status.as_deref()) {
+    (Some(current), Some("Charging")) => (
+      estimate_seconds(path, capacity_percent, current, Direction::ToFull),
+      None,
+    ),
+    (Some(current), Some("Discharging")) => (
+      None,
+      estimate_seconds(path, capacity_percent, current, Direction::ToEmpty),
+    ),
+    _ => (None, None),
+  };
+
+  Some(BatteryData {
+    name,
+    capacity_percent,
+    status,
+    health: fsutil::read_trimmed(&path.join("health")),
+    // Reported in tenths of a degree Celsius
+    millidegrees: number("temp").map(|tenths| tenths * 100),
+    current_microamps,
+    voltage_microvolts: number("voltage_now"),
+    seconds_to_full,
+    seconds_to_empty,
+  })
+}
+
+enum Direction {
+  ToFull,
+  ToEmpty,
+}
+
+// Time until the battery is full or empty at the present current draw, from the charge
+// counters in µAh (charge_counter on Android, charge_now elsewhere). Without a current charge
+// reading it is derived from the capacity percentage.
+fn estimate_seconds(
+  path: &Path,
+  capacity_percent: u32,
+  current_microamps: i64,
+  direction: Direction,
+) -> Option<u64> {
+  let number = |attribute: &str| -> Option<f64> {
+    fsutil::read_trimmed(&path.join(attribute))?
+      .parse()
+      .ok()
+      .filter(|value: &f64| *value > 0.0)
+  };
+
+  let full = number("charge_full").or_else(|| number("charge_full_design"))?;
+  let now = number("charge_now")
+    .or_else(|| number("charge_counter"))
+    .unwrap_or(full * capacity_percent as f64 / 100.0);
+
+  let remaining = match direction {
+    Direction::ToFull => (full - now).max(0.0),
+    Direction::ToEmpty => now,
+  };
+  let hours = remaining / current_microamps.unsigned_abs() as f64;
+  Some((hours * 3600.0) as u64)
+}
+
+// "5h 12m", or just minutes below an hour
+fn format_duration(seconds: u64) -> String {
+  let minutes = seconds / 60;
+  match (minutes / 60, minutes % 60) {
+    (0, minutes) => format!("{}m", minutes),
+    (hours, minutes) => format!("{}h {}m", hours, minutes),
+  }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::fsutil;
+
+// Flag passed to the detached background process that refreshes stale entries
+pub const REFRESH_FLAG: &str = "--refresh-cache";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheMode {
+  // Always compute values
+  Off,
+  // Reuse values until their TTL expires
+  #[default]
+  Ttl,
+  // Show expired values immediately and refresh them in the background
+  StaleWhileRevalidate,
+  // Recompute and store every value (used by the background refresh)
+  #[serde(skip)]
+  Refresh,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+  value: Value,
+  stored_at: u64,
+  ttl: u64,
+}
+
+type CacheFile = HashMap<String, Entry>;
+
+static MODE: OnceLock<CacheMode> = OnceLock::new();
+static REFRESH_NEEDED: AtomicBool = AtomicBool::new(false);
+// Serializes read-modify-write of cache files between module threads
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn init(mode: CacheMode) {
+  let _ = MODE.set(mode);
+}
+
+fn mode() -> CacheMode {
+  MODE.get().copied().unwrap_or_default()
+}
+
+// Return the cached value of `module`/`field`, or compute and store it. Failed computations
+// (None) are not cached so they are retried on the next run.
+pub fn cached<T: Serialize + DeserializeOwned>(
+  module: &str,
+  field: &str,
+  ttl: Duration,
+  compute: impl FnOnce() -> Option<T>,
+) -> Option<T> {
+  let mode = mode();
+  if mode == CacheMode::Off {
+    return compute();
+  }
+
+  if mode != CacheMode::Refresh
+    && let Some(entry) = read_entry(module, field)
+    && let Ok(value) = serde_json::from_value(entry.value)
+  {
+    if unix_now().saturating_sub(entry.stored_at) < entry.ttl {
+      return Some(value);
+    }
+    if mode == CacheMode::StaleWhileRevalidate {
+      REFRESH_NEEDED.store(true, Ordering::Relaxed);
+      return Some(value);
+    }
+  }
+
+  let value = compute()?;
+  write_entry(module, field, &value, ttl);
+  Some(value)
+}
+
+// Start a detached copy of this process that recomputes the stale entries that were just shown.
+// It gets its own process group so the login shell neither waits for it nor kills it.
+pub fn spawn_refresh_if_needed() {
+  if !REFRESH_NEEDED.load(Ordering::Relaxed) {
+    return;
+  }
+
+  let Ok(exe) = env::current_exe() else {
+    return;
+  };
+
+  let _ = Command::new(exe)
+    .args(env::args().skip(1))
+    .arg(REFRESH_FLAG)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .process_group(0)
+    .spawn();
+}
+
+fn cache_file(module: &str) -> Option<PathBuf> {
+  fsutil::xdg_dir("XDG_CACHE_HOME", ".cache").map(|dir| dir.join(format!("{}.json", module)))
+}
+
+fn read_file(module: &str) -> CacheFile {
+  cache_file(module)
+    .and_then(|path| fs::read(path).ok())
+    .and_then(|contents| serde_json::from_slice(&contents).ok())
+    .unwrap_or_default()
+}
+
+fn read_entry(
+  module: &str,
+  field: &str,
+) -> Option<Entry> {
+  let _lock = FILE_LOCK.lock();
+  read_file(module).remove(field)
+}
+
+fn write_entry(
+  module: &str,
+  field: &str,
+  value: &impl Serialize,
+  ttl: Duration,
+) {
+  let Some(path) = cache_file(module) else {
+    return;
+  };
+  let Ok(value) = serde_json::to_value(value) else {
+    return;
+  };
+
+  let _lock = FILE_LOCK.lock();
+  let mut file = read_file(module);
+  // Drop entries that have been expired for another whole TTL, such as keys that are no longer
+  // asked for. Recently expired ones stay so stale-while-revalidate can still show them.
+  let now = unix_now();
+  file.retain(|_, entry| now.saturating_sub(entry.stored_at) < entry.ttl.saturating_mul(2));
+  file.insert(
+    field.to_string(),
+    Entry {
+      value,
+      stored_at: now,
+      ttl: ttl.as_secs(),
+    },
+  );
+
+  // The cache is best effort, errors just mean the value is computed again next time
+  if let Ok(contents) = serde_json::to_vec(&file) {
+    let _ = fsutil::atomic_write(&path, &contents);
+  }
+}
+
+fn unix_now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |now| now.as_secs())
+}
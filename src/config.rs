@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::cache::{self, CacheMode};
+use crate::fsutil;
+use crate::layout::Layout;
+use crate::module::Module;
+use crate::registry;
+use crate::style::{self, ColorChoice, ThemeSpec};
+use crate::temperature::TemperatureUnit;
+
+// Render budget when none is configured, generous enough for a cold start on a slow phone
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+  #[default]
+  Text,
+  Json,
+}
+
+// Layout of config.toml
+#[derive(Debug, Default)]
+struct FileConfig {
+  modules: Option<Vec<String>>,
+  format: OutputFormat,
+  layout: Layout,
+  timeout_ms: Option<u64>,
+  cache: CacheMode,
+  color: ColorChoice,
+  theme: Option<String>,
+  themes: HashMap<String, ThemeSpec>,
+  temperature_unit: TemperatureUnit,
+  options: HashMap<String, toml::Table>,
+}
+
+// Per-module options from the `[options.<module>]` tables
+#[derive(Debug, Clone, Default)]
+pub struct ModuleOptions {
+  module: String,
+  table: toml::Table,
+}
+
+// Options already reported as invalid, so options read once per sensor or mount warn only once
+static REPORTED_OPTIONS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+impl ModuleOptions {
+  // The value of `key`, or None when it is not set. A value of the wrong type is reported and
+  // treated as unset, like an invalid key in the config file itself.
+  pub fn get<T: DeserializeOwned>(
+    &self,
+    key: &str,
+  ) -> Option<T> {
+    match self.table.get(key)?.clone().try_into() {
+      Ok(value) => Some(value),
+      Err(err) => {
+        let mut reported = REPORTED_OPTIONS
+          .lock()
+          .unwrap_or_else(PoisonError::into_inner);
+        let option = (self.module.clone(), key.to_string());
+        if !reported.contains(&option) {
+          eprintln!(
+            "Invalid value for {} in [options.{}]: {}",
+            key,
+            self.module,
+            err.message()
+          );
+          reported.push(option);
+        }
+        None
+      }
+    }
+  }
+}
+
+pub struct Config {
+  pub format: OutputFormat,
+  pub layout: Layout,
+  // Overall render budget; slower modules are shown as timed out
+  pub timeout: Duration,
+  pub cache: CacheMode,
+  pub color: ColorChoice,
+  pub theme: String,
+  // User-defined themes from the [themes.<name>] tables
+  pub themes: HashMap<String, ThemeSpec>,
+  // Unit for every module showing temperatures, unless a module sets its own `unit`
+  pub temperature_unit: TemperatureUnit,
+  enabled_modules: Vec<&'static dyn Module>,
+  options: HashMap<String, toml::Table>,
+}
+
+impl Config {
+  pub fn new() -> Self {
+    let args = expanded_args();
+
+    // An explicit --config path wins over the default search locations
+    let config_path = args
+      .windows(2)
+      .find(|pair| pair[0] == "-c" || pair[0] == "--config")
+      .map(|pair| PathBuf::from(&pair[1]))
+      .or_else(find_config_file);
+
+    let file_config = config_path.map(load_config_file).unwrap_or_default();
+
+    let enabled_modules = match file_config.modules {
+      Some(modules) => modules
+        .iter()
+        .filter_map(|name| {
+          let module = registry::find(name);
+          if module.is_none() {
+            eprintln!("Unknown module in config: {}", name);
+          }
+          module
+        })
+        .collect(),
+      None => registry::DEFAULT_MODULES
+        .iter()
+        .filter_map(|name| registry::find(name))
+        .collect(),
+    };
+
+    Config {
+      format: file_config.format,
+      layout: file_config.layout,
+      timeout: Duration::from_millis(file_config.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)),
+      cache: file_config.cache,
+      color: file_config.color,
+      theme: file_config.theme.unwrap_or_else(|| "default".to_string()),
+      themes: file_config.themes,
+      temperature_unit: file_config.temperature_unit,
+      enabled_modules,
+      options: file_config.options,
+    }
+  }
+
+  pub fn parse_args(&mut self) {
+    let args = expanded_args();
+    let mut flagged_modules = Vec::new();
+
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+      if let Some(module) = module_for_flag(arg) {
+        flagged_modules.push(module);
+        continue;
+      }
+
+      match arg.as_str() {
+        "-c" | "--config" => {
+          // Already handled while loading the config file
+          if args_iter.next().is_none() {
+            eprintln!("Missing path after {}", arg);
+            std::process::exit(1);
+          }
+        }
+        "-f" | "--format" => {
+          self.format = match args_iter.next().map(String::as_str) {
+            Some("text") => OutputFormat::Text,
+            Some("json") => OutputFormat::Json,
+            _ => {
+              eprintln!("Expected text or json after {}", arg);
+              std::process::exit(1);
+            }
+          };
+        }
+        "--layout" => {
+          self.layout = match args_iter.next().map(String::as_str) {
+            Some("stacked") => Layout::Stacked,
+            Some("side-by-side") => Layout::SideBySide,
+            _ => {
+              eprintln!("Expected stacked or side-by-side after {}", arg);
+              std::process::exit(1);
+            }
+          };
+        }
+        "--timeout" => match args_iter.next().and_then(|ms| ms.parse().ok()) {
+          Some(ms) => self.timeout = Duration::from_millis(ms),
+          None => {
+            eprintln!("Expected milliseconds after {}", arg);
+            std::process::exit(1);
+          }
+        },
+        "--cache" => {
+          self.cache = match args_iter.next().map(String::as_str) {
+            Some("off") => CacheMode::Off,
+            Some("ttl") => CacheMode::Ttl,
+            Some("stale-while-revalidate") => CacheMode::StaleWhileRevalidate,
+            _ => {
+              eprintln!("Expected off, ttl or stale-while-revalidate after {}", arg);
+              std::process::exit(1);
+            }
+          };
+        }
+        "--color" => {
+          self.color = match args_iter.next().map(String::as_str) {
+            Some("auto") => ColorChoice::Auto,
+            Some("always") => ColorChoice::Always,
+            Some("never") => ColorChoice::Never,
+            _ => {
+              eprintln!("Expected auto, always or never after {}", arg);
+              std::process::exit(1);
+            }
+          };
+        }
+        "--theme" => match args_iter.next() {
+          Some(theme) => self.theme = theme.clone(),
+          None => {
+            eprintln!("Missing theme name after {}", arg);
+            std::process::exit(1);
+          }
+        },
+        "--unit" => {
+          self.temperature_unit = match args_iter
+            .next()
+            .and_then(|unit| TemperatureUnit::parse(unit))
+          {
+            Some(unit) => unit,
+            None => {
+              eprintln!("Expected C, F or K after {}", arg);
+              std::process::exit(1);
+            }
+          };
+        }
+        cache::REFRESH_FLAG => self.cache = CacheMode::Refresh,
+        "-h" | "--help" => {
+          self.show_help();
+          std::process::exit(0);
+        }
+        _ => {
+          eprintln!("Unknown flag: {}", arg);
+          eprintln!("Use -h or --help for usage information");
+          std::process::exit(1);
+        }
+      }
+    }
+
+    // Module flags override the module list from the config file and are shown in categorical order
+    if !flagged_modules.is_empty() {
+      self.enabled_modules = registry::all()
+        .iter()
+        .copied()
+        .filter(|module| {
+          flagged_modules
+            .iter()
+            .any(|flagged| flagged.name() == module.name())
+        })
+        .collect();
+      self.enabled_modules.sort_by_key(|module| module.category());
+    }
+  }
+
+  fn show_help(&self) {
+    println!("System Information Display");
+    println!();
+    println!(
+      "Usage: {} [FLAGS]",
+      env::args().next().unwrap_or_else(|| "program".to_string())
+    );
+    println!();
+    println!("Available modules:");
+    for module in registry::all() {
+      let flag = module
+        .flag()
+        .map(|flag| format!("-{}", flag))
+        .unwrap_or_default();
+      println!(
+        "  {:<4}  {:<20}{}",
+        flag,
+        module.name(),
+        module.description()
+      );
+    }
+    println!();
+    println!("  -c, --config <PATH>    Use a specific config file");
+    println!("  -f, --format <FORMAT>  Output format: text (default) or json");
+    println!("      --layout <LAYOUT>  stacked (default) or side-by-side (logo left, info right)");
+    println!(
+      "      --timeout <MS>     Overall render budget in milliseconds (default {})",
+      DEFAULT_TIMEOUT_MS
+    );
+    println!("      --color <WHEN>     auto (default, honors NO_COLOR), always or never");
+    println!(
+      "      --theme <NAME>     Color theme: {} or one from the config file",
+      style::BUILTIN_THEMES.join(", ")
+    );
+    println!("      --unit <UNIT>      Temperature unit: C (default), F or K");
+    println!("      --cache <MODE>     off, ttl (default) or stale-while-revalidate");
+    println!("  -h, --help             Show this help message");
+    println!();
+    println!(
+      "Default modules (when no flags or config provided): {}",
+      registry::DEFAULT_MODULES.join(", ")
+    );
+    println!();
+    println!("Config file locations (first match wins):");
+    println!("  $XDG_CONFIG_HOME/rusty-motd/config.toml");
+    println!("  $PREFIX/etc/motd/config.toml");
+  }
+
+  pub fn get_enabled_modules(&self) -> Vec<&'static dyn Module> {
+    self.enabled_modules.clone()
+  }
+
+  pub fn module_options(
+    &self,
+    module: &str,
+  ) -> ModuleOptions {
+    ModuleOptions {
+      module: module.to_string(),
+      table: self.options.get(module).cloned().unwrap_or_default(),
+    }
+  }
+}
+
+// Command line arguments with `--option=value` split into `--option value`
+fn expanded_args() -> Vec<String> {
+  env::args()
+    .flat_map(|arg| match arg.split_once('=') {
+      Some((option, value)) if option.starts_with("--") => {
+        vec![option.to_string(), value.to_string()]
+      }
+      _ => vec![arg],
+    })
+    .collect()
+}
+
+fn module_for_flag(arg: &str) -> Option<&'static dyn Module> {
+  let mut chars = arg.strip_prefix('-')?.chars();
+  match (chars.next(), chars.next()) {
+    (Some(flag), None) => registry::find_by_flag(flag),
+    _ => None,
+  }
+}
+
+fn find_config_file() -> Option<PathBuf> {
+  let xdg_config = fsutil::xdg_dir("XDG_CONFIG_HOME", ".config");
+  let prefix = env::var("PREFIX").unwrap_or_default();
+
+  xdg_config
+    .map(|dir| dir.join("config.toml"))
+    .into_iter()
+    .chain([PathBuf::from(format!("{}/etc/motd/config.toml", prefix))])
+    .find(|path| path.is_file())
+}
+
+fn load_config_file(path: PathBuf) -> FileConfig {
+  // A broken config should never keep the MOTD from showing, so fall back to defaults
+  let contents = match fs::read_to_string(&path) {
+    Ok(contents) => contents,
+    Err(err) => {
+      eprintln!("Error reading config {}: {}", path.display(), err);
+      return FileConfig::default();
+    }
+  };
+
+  let table: toml::Table = match toml::from_str(&contents) {
+    Ok(table) => table,
+    Err(err) => {
+      eprintln!("Error parsing config {}: {}", path.display(), err);
+      return FileConfig::default();
+    }
+  };
+
+  // Unknown keys and values of the wrong type are reported and skipped, keeping the rest
+  let mut config = FileConfig::default();
+  for (key, value) in table {
+    let result = match key.as_str() {
+      "modules" => value
+        .try_into()
+        .map(|modules| config.modules = Some(modules)),
+      "format" => value.try_into().map(|format| config.format = format),
+      "layout" => value.try_into().map(|layout| config.layout = layout),
+      "timeout_ms" => value
+        .try_into()
+        .map(|timeout| config.timeout_ms = Some(timeout)),
+      "cache" => value.try_into().map(|cache| config.cache = cache),
+      "color" => value.try_into().map(|color| config.color = color),
+      "theme" => value.try_into().map(|theme| config.theme = Some(theme)),
+      "temperature_unit" => value.try_into().map(|unit| config.temperature_unit = unit),
+      "themes" | "options" => {
+        load_tables(&path, &key, value, &mut config);
+        continue;
+      }
+      _ => {
+        eprintln!("Unknown key in config {}: {}", path.display(), key);
+        continue;
+      }
+    };
+    if let Err(err) = result {
+      eprintln!(
+        "Invalid value for {} in config {}: {}",
+        key,
+        path.display(),
+        err.message()
+      );
+    }
+  }
+  config
+}
+
+// [themes.<name>] and [options.<module>], one table at a time so a mistake only drops its own
+fn load_tables(
+  path: &Path,
+  key: &str,
+  value: toml::Value,
+  config: &mut FileConfig,
+) {
+  let toml::Value::Table(tables) = value else {
+    eprintln!(
+      "Invalid value for {} in config {}: expected a table",
+      key,
+      path.display()
+    );
+    return;
+  };
+
+  for (name, value) in tables {
+    let result = if key == "themes" {
+      value.try_into().map(|theme| {
+        config.themes.insert(name.clone(), theme);
+      })
+    } else {
+      value.try_into().map(|options| {
+        config.options.insert(name.clone(), options);
+      })
+    };
+    if let Err(err) = result {
+      eprintln!(
+        "Invalid value for {}.{} in config {}: {}",
+        key,
+        name,
+        path.display(),
+        err.message()
+      );
+    }
+  }
+}
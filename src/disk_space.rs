@@ -1,97 +1,183 @@
-use std::io::{self, BufRead};
-use std::process::{Command, Stdio};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
 
-pub fn run() -> String {
-  // Configuration
-  const MAX_USAGE: u32 = 95;
-  const ALERT_USAGE: u32 = 75;
-  const BAR_WIDTH: usize = 50;
+use serde::Serialize;
+
+use crate::bar;
+use crate::glob::matches_any;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::units::format_bytes_si;
+
+pub struct DiskSpace;
+
+#[derive(Serialize)]
+struct DiskUsage {
+  mounts: Vec<Mount>,
+}
+
+#[derive(Serialize)]
+struct Mount {
+  mount_point: String,
+  fs_type: String,
+  source: String,
+  used_bytes: u64,
+  available_bytes: u64,
+  total_bytes: u64,
+  usage_percent: u32,
+}
+
+// Virtual filesystems that never hold user data
+const DEFAULT_EXCLUDE_TYPES: &[&str] = &[
+  "autofs",
+  "binfmt_misc",
+  "bpf",
+  "cgroup",
+  "cgroup2",
+  "configfs",
+  "debugfs",
+  "devpts",
+  "devtmpfs",
+  "efivarfs",
+  "functionfs",
+  "fusectl",
+  "hugetlbfs",
+  "mqueue",
+  "nsfs",
+  "proc",
+  "pstore",
+  "ramfs",
+  "rootfs",
+  "securityfs",
+  "selinuxfs",
+  "sysfs",
+  "tmpfs",
+  "tracefs",
+];
+
+// One line of /proc/self/mountinfo
+struct MountEntry {
+  device: String,
+  mount_point: String,
+  read_only: bool,
+  fs_type: String,
+  source: String,
+}
+
+impl Module for DiskSpace {
+  fn name(&self) -> &'static str {
+    "disk-space"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('d')
+  }
+
+  fn category(&self) -> Category {
+    Category::Storage
+  }
+
+  fn description(&self) -> &'static str {
+    "Disk usage information"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.disk-space] table)
+  let max_usage: u32 = options.get("max_usage").unwrap_or(95);
+  let alert_usage: u32 = options.get("alert_usage").unwrap_or(75);
+  let bar_width: usize = options.get("bar_width").unwrap_or(50);
+
+  // Mount filters: include lists are ignored when empty, exclude_types replaces the defaults
+  let include_types: Vec<String> = options.get("include_types").unwrap_or_default();
+  let exclude_types: Vec<String> = options.get("exclude_types").unwrap_or_else(|| {
+    DEFAULT_EXCLUDE_TYPES
+      .iter()
+      .map(|fs_type| fs_type.to_string())
+      .collect()
+  });
+  let include_paths: Vec<String> = options.get("include_paths").unwrap_or_default();
+  let exclude_paths: Vec<String> = options.get("exclude_paths").unwrap_or_default();
+  let show_readonly: bool = options.get("show_readonly").unwrap_or(false);
 
   // ANSI color codes
-  const GREEN: &str = "\x1B[1;32m";
-  const RED: &str = "\x1B[1;31m";
-  const YELLOW: &str = "\x1B[1;33m";
-  const BOLD: &str = "\x1B[1m";
-  const NC: &str = "\x1B[0m";
-
-  // Unicode bar components
-  const BAR_START: &str = "\u{ee03}";
-  const BAR_FILLED: &str = "\u{ee04}";
-  const BAR_EMPTY: &str = "\u{ee01}";
-  const BAR_END_FULL: &str = "\u{ee05}";
-  const BAR_END_EMPTY: &str = "\u{ee02}";
+  let theme = style::theme();
 
-  let mut output = String::new();
-  output.push_str(&format!("\n{}Disk Usage:{}\n", BOLD, NC));
-
-  // Get disk usage information with more compatible df command
-  let child = match Command::new("df")
-    .args(["-H", "-t", "fuse"])
-    .stdout(Stdio::piped())
-    .spawn()
-  {
-    Ok(child) => child,
-    Err(_) => {
-      output.push_str(&format!("  {}Error running df command{}\n", RED, NC));
-      return output;
-    }
-  };
+  let mountinfo = fs::read_to_string("/proc/self/mountinfo")
+    .map_err(|_| ModuleError::Failed("Error reading /proc/self/mountinfo".to_string()))?;
 
-  let reader = io::BufReader::new(child.stdout.unwrap());
+  let entries: Vec<MountEntry> = mountinfo
+    .lines()
+    .filter_map(parse_mountinfo_line)
+    .filter(|entry| show_readonly || !entry.read_only)
+    .filter(|entry| include_types.is_empty() || include_types.contains(&entry.fs_type))
+    .filter(|entry| !exclude_types.contains(&entry.fs_type))
+    .filter(|entry| include_paths.is_empty() || matches_any(&include_paths, &entry.mount_point))
+    .filter(|entry| !matches_any(&exclude_paths, &entry.mount_point))
+    .collect();
 
-  for line in reader.lines().skip(1) {
-    let line = match line {
-      Ok(line) => line,
-      Err(_) => continue,
-    };
+  // Bind mounts show up once per mount point, keep the shortest path for each device
+  let mut shortest: HashMap<&str, &MountEntry> = HashMap::new();
+  for entry in &entries {
+    shortest
+      .entry(&entry.device)
+      .and_modify(|current| {
+        if entry.mount_point.len() < current.mount_point.len() {
+          *current = entry;
+        }
+      })
+      .or_insert(entry);
+  }
 
-    let fields: Vec<&str> = line.split_whitespace().collect();
+  let mut data = DiskUsage { mounts: Vec::new() };
+  let mut output = String::new();
+  output.push_str(&format!("\n{}Disk Usage:{}\n", theme.bold, theme.reset));
 
-    if fields.len() < 6 {
+  for entry in &entries {
+    if !std::ptr::eq(shortest[entry.device.as_str()], entry) {
       continue;
     }
 
-    // Different df versions have different column orders
-    let usage_percent = if fields[4].ends_with('%') {
-      fields[4]
-    } else if fields[5].ends_with('%') {
-      fields[5]
-    } else {
+    let Some((total, free, available)) = statvfs(&entry.mount_point) else {
       continue;
     };
 
-    let usage = usage_percent
-      .trim_end_matches('%')
-      .parse::<u32>()
-      .unwrap_or(0);
-    let used_space = fields[2];
-    let total_space = fields[1];
-    let mount_point = fields.last().unwrap();
+    // Pseudo filesystems that slipped through the filters report no blocks
+    if total == 0 {
+      continue;
+    }
 
-    // Calculate bar width
-    let used_width = (usage as usize * BAR_WIDTH) / 100;
+    // Same rounding as df: used / (used + available), rounded up
+    let used = total.saturating_sub(free);
+    let usage = match used + available {
+      0 => 0,
+      usable => (used * 100).div_ceil(usable) as u32,
+    };
+
+    let used_space = format_bytes_si(used);
+    let total_space = format_bytes_si(total);
+    let mount_point = &entry.mount_point;
 
     // Determine color
-    let color = if usage >= MAX_USAGE {
-      RED
-    } else if usage >= ALERT_USAGE {
-      YELLOW
+    let color = if usage >= max_usage {
+      &theme.critical
+    } else if usage >= alert_usage {
+      &theme.warn
     } else {
-      GREEN
+      &theme.ok
     };
 
-    // Build the bar
-    let mut bar = String::new();
-    bar.push_str(color);
-    bar.push_str(BAR_START);
-    bar.push_str(&BAR_FILLED.repeat(used_width));
-    bar.push_str(&BAR_EMPTY.repeat(BAR_WIDTH - used_width));
-    bar.push_str(if usage == MAX_USAGE {
-      BAR_END_FULL
-    } else {
-      BAR_END_EMPTY
-    });
-    bar.push_str(NC);
+    let bar = bar::render(usage, bar_width, color);
 
     // Add to output
     output.push_str(&format!(
@@ -99,7 +185,80 @@ pub fn run() -> String {
       mount_point, used_space, total_space
     ));
     output.push_str(&format!("  {}\n", bar));
+
+    data.mounts.push(Mount {
+      mount_point: mount_point.clone(),
+      fs_type: entry.fs_type.clone(),
+      source: entry.source.clone(),
+      used_bytes: used,
+      available_bytes: available,
+      total_bytes: total,
+      usage_percent: usage,
+    });
+  }
+
+  Ok(Output::new(output, &data))
+}
+
+// Format: id parent major:minor root mount_point options [optional...] - fs_type source super_options
+fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+  let (mount_fields, fs_fields) = line.split_once(" - ")?;
+  let mut mount_fields = mount_fields.split(' ');
+  let mut fs_fields = fs_fields.split(' ');
+
+  let device = mount_fields.nth(2)?;
+  let mount_point = mount_fields.nth(1)?;
+  let mount_options = mount_fields.next()?;
+
+  Some(MountEntry {
+    device: device.to_string(),
+    mount_point: unescape_octal(mount_point),
+    read_only: mount_options.split(',').any(|option| option == "ro"),
+    fs_type: fs_fields.next()?.to_string(),
+    source: unescape_octal(fs_fields.next()?),
+  })
+}
+
+// The kernel escapes space, tab, newline and backslash as \ooo in mountinfo
+fn unescape_octal(field: &str) -> String {
+  let bytes = field.as_bytes();
+  let mut unescaped = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let octal = bytes.get(i + 1..i + 4).and_then(|digits| {
+      let digits = std::str::from_utf8(digits).ok()?;
+      u8::from_str_radix(digits, 8).ok()
+    });
+
+    match (bytes[i], octal) {
+      (b'\\', Some(byte)) => {
+        unescaped.push(byte);
+        i += 4;
+      }
+      (byte, _) => {
+        unescaped.push(byte);
+        i += 1;
+      }
+    }
+  }
+
+  String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+// Returns (total, free, available) bytes for the filesystem containing path
+fn statvfs(path: &str) -> Option<(u64, u64, u64)> {
+  let c_path = CString::new(path).ok()?;
+  // SAFETY: statvfs is plain old data and is only read after the call succeeds
+  let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+    return None;
   }
 
-  output
+  let fragment_size = stat.f_frsize as u64;
+  Some((
+    stat.f_blocks as u64 * fragment_size,
+    stat.f_bfree as u64 * fragment_size,
+    stat.f_bavail as u64 * fragment_size,
+  ))
 }
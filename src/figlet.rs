@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Fonts compiled into the binary, by name
+const BUILTIN: &[(&str, &str)] = &[
+  ("standard", include_str!("../fonts/standard.flf")),
+  ("block", include_str!("../fonts/block.flf")),
+  ("banner", include_str!("../fonts/banner.flf")),
+];
+
+pub const DEFAULT_FONT: &str = "standard";
+
+// Characters every font defines after ASCII 32-126: Ä Ö Ü ä ö ü ß
+const DEUTSCH: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+// Horizontal bits of the full_layout header field
+const SMUSH_EQUAL: u32 = 1;
+const SMUSH_LOWLINE: u32 = 2;
+const SMUSH_HIERARCHY: u32 = 4;
+const SMUSH_PAIR: u32 = 8;
+const SMUSH_BIG_X: u32 = 16;
+const SMUSH_HARDBLANK: u32 = 32;
+const FITTING: u32 = 64;
+const SMUSHING: u32 = 128;
+
+// Characters an underscore gives way to, and the classes of the hierarchy rule (later wins)
+const LOWLINE_REPLACEMENTS: &str = "|/\\[]{}()<>";
+const HIERARCHY: &[&str] = &["|", "/\\", "[]", "{}", "()", "<>"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+  // Every character keeps its full width
+  FullWidth,
+  // Characters are moved together until they touch
+  Fitting,
+  // Characters overlap by one column where the rules (bits of full_layout) allow it;
+  // no rules means universal smushing
+  Smushing(u32),
+}
+
+type Glyph = Vec<Vec<char>>;
+
+// A parsed FIGlet font (flf2a format, see figfont.txt from the FIGlet distribution)
+pub struct Font {
+  hardblank: char,
+  height: usize,
+  layout: Layout,
+  glyphs: HashMap<char, Glyph>,
+}
+
+impl Font {
+  // A built-in font by name, a font installed with figlet by name ("slant"), otherwise a .flf
+  // file at the given path
+  pub fn find(font: &str) -> Result<Font, String> {
+    if let Some((name, source)) = BUILTIN.iter().find(|(name, _)| *name == font) {
+      return Font::parse(source).map_err(|err| format!("Built-in font {}: {}", name, err));
+    }
+
+    let is_path = font.contains('/') || font.ends_with(".flf");
+    if !is_path {
+      return font_dirs()
+        .into_iter()
+        .map(|dir| dir.join(format!("{}.flf", font)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("Unknown font: {}", font))
+        .and_then(|path| Font::load(&path));
+    }
+    Font::load(Path::new(font))
+  }
+
+  pub fn load(path: &Path) -> Result<Font, String> {
+    let source = fs::read_to_string(path)
+      .map_err(|err| format!("Cannot read font {}: {}", path.display(), err))?;
+    Font::parse(&source).map_err(|err| format!("Invalid font {}: {}", path.display(), err))
+  }
+
+  pub fn parse(source: &str) -> Result<Font, String> {
+    let mut lines = source.lines();
+
+    // flf2a<hardblank> height baseline max_length old_layout comment_lines
+    //   [print_direction [full_layout [codetag_count]]]
+    let header = lines.next().ok_or("empty file")?;
+    let mut signature = header
+      .strip_prefix("flf2a")
+      .ok_or("missing flf2a signature")?
+      .chars();
+    let hardblank = signature.next().ok_or("missing hardblank")?;
+    let fields: Vec<i64> = signature
+      .as_str()
+      .split_whitespace()
+      .map(|field| {
+        field
+          .parse()
+          .map_err(|_| format!("bad header field {}", field))
+      })
+      .collect::<Result<_, _>>()?;
+    let [
+      height,
+      _baseline,
+      _max_length,
+      old_layout,
+      comment_lines,
+      ..,
+    ] = fields[..]
+    else {
+      return Err("incomplete header".to_string());
+    };
+    if height < 1 {
+      return Err("height must be positive".to_string());
+    }
+    let height = height as usize;
+
+    let mut font = Font {
+      hardblank,
+      height,
+      layout: layout(old_layout, fields.get(6).copied()),
+      glyphs: HashMap::new(),
+    };
+
+    for _ in 0..comment_lines {
+      lines.next();
+    }
+
+    // Required characters in order. Some fonts stop early, which is tolerated.
+    for code in (32..=126).chain(DEUTSCH) {
+      let Some(glyph) = read_glyph(&mut lines, height) else {
+        return Ok(font);
+      };
+      if let Some(character) = char::from_u32(code) {
+        font.glyphs.insert(character, glyph);
+      }
+    }
+
+    // Code-tagged characters: a line with the code (decimal, 0x hex or 0 octal) and a comment
+    while let Some(tag) = lines.next() {
+      let Some(code) = tag.split_whitespace().next() else {
+        continue;
+      };
+      let code = parse_code(code).ok_or_else(|| format!("bad character code {}", code))?;
+      let glyph =
+        read_glyph(&mut lines, height).ok_or_else(|| format!("truncated character {}", code))?;
+      // Negative codes are translation-table entries that cannot be typed
+      if let Some(character) = u32::try_from(code).ok().and_then(char::from_u32) {
+        font.glyphs.insert(character, glyph);
+      }
+    }
+
+    Ok(font)
+  }
+
+  // Render `text`, wrapping onto a new row of characters before `max_width` columns.
+  // Characters missing from the font are skipped.
+  pub fn render(
+    &self,
+    text: &str,
+    max_width: usize,
+  ) -> String {
+    let mut blocks = Vec::new();
+
+    for line in text.lines() {
+      let mut rows: Glyph = vec![Vec::new(); self.height];
+      let mut previous_width = 0;
+
+      for character in line.chars() {
+        let Some(glyph) = self.glyphs.get(&character) else {
+          continue;
+        };
+        let width = glyph_width(glyph);
+
+        let mut amount = self.smush_amount(&rows, glyph, previous_width);
+        if previous_width > 0 && rows[0].len() + width - amount.min(width) > max_width {
+          blocks.push(std::mem::replace(&mut rows, vec![Vec::new(); self.height]));
+          previous_width = 0;
+          amount = self.smush_amount(&rows, glyph, previous_width);
+        }
+
+        self.add_glyph(&mut rows, glyph, amount, previous_width);
+        previous_width = width;
+      }
+
+      blocks.push(rows);
+    }
+
+    let mut output = String::new();
+    for row in blocks.iter().flatten() {
+      let row: String = row
+        .iter()
+        .map(|&c| if c == self.hardblank { ' ' } else { c })
+        .collect();
+      output.push_str(row.trim_end());
+      output.push('\n');
+    }
+    output
+  }
+
+  // How many columns the glyph can overlap the output so far: the free space between them
+  // on the tightest row, plus one where the touching characters smush
+  fn smush_amount(
+    &self,
+    rows: &[Vec<char>],
+    glyph: &[Vec<char>],
+    previous_width: usize,
+  ) -> usize {
+    if self.layout == Layout::FullWidth {
+      return 0;
+    }
+
+    let width = glyph_width(glyph);
+    let mut amount = width;
+
+    for (row, glyph_row) in rows.iter().zip(glyph) {
+      let leading = glyph_row
+        .iter()
+        .position(|&c| c != ' ')
+        .unwrap_or(glyph_row.len());
+
+      let row_amount = match row.iter().rposition(|&c| c != ' ') {
+        None => leading + row.len(),
+        Some(last) => {
+          let gap = leading + row.len() - 1 - last;
+          let touching = glyph_row
+            .get(leading)
+            .and_then(|&right| self.smush(row[last], right, previous_width, width));
+          gap + usize::from(touching.is_some())
+        }
+      };
+      amount = amount.min(row_amount);
+    }
+
+    amount
+  }
+
+  fn add_glyph(
+    &self,
+    rows: &mut [Vec<char>],
+    glyph: &[Vec<char>],
+    amount: usize,
+    previous_width: usize,
+  ) {
+    let width = glyph_width(glyph);
+
+    for (row, glyph_row) in rows.iter_mut().zip(glyph) {
+      for k in 0..amount {
+        let Some(column) = (row.len() + k).checked_sub(amount) else {
+          continue;
+        };
+        let right = glyph_row.get(k).copied().unwrap_or(' ');
+        row[column] = self
+          .smush(row[column], right, previous_width, width)
+          .unwrap_or(right);
+      }
+      row.extend(glyph_row.iter().skip(amount));
+    }
+  }
+
+  // The character two overlapping characters turn into, if they may overlap at all
+  fn smush(
+    &self,
+    left: char,
+    right: char,
+    previous_width: usize,
+    width: usize,
+  ) -> Option<char> {
+    if left == ' ' {
+      return Some(right);
+    }
+    if right == ' ' {
+      return Some(left);
+    }
+    // Very narrow characters are never smushed
+    if previous_width < 2 || width < 2 {
+      return None;
+    }
+    let Layout::Smushing(rules) = self.layout else {
+      return None;
+    };
+
+    let hardblank = self.hardblank;
+    if rules == 0 {
+      return Some(if right == hardblank { left } else { right });
+    }
+
+    if rules & SMUSH_HARDBLANK != 0 && left == hardblank && right == hardblank {
+      return Some(left);
+    }
+    if left == hardblank || right == hardblank {
+      return None;
+    }
+
+    if rules & SMUSH_EQUAL != 0 && left == right {
+      return Some(left);
+    }
+
+    if rules & SMUSH_LOWLINE != 0 {
+      if left == '_' && LOWLINE_REPLACEMENTS.contains(right) {
+        return Some(right);
+      }
+      if right == '_' && LOWLINE_REPLACEMENTS.contains(left) {
+        return Some(left);
+      }
+    }
+
+    if rules & SMUSH_HIERARCHY != 0 {
+      let class = |c: char| HIERARCHY.iter().position(|class| class.contains(c));
+      if let (Some(left_class), Some(right_class)) = (class(left), class(right))
+        && left_class != right_class
+      {
+        return Some(if left_class > right_class {
+          left
+        } else {
+          right
+        });
+      }
+    }
+
+    if rules & SMUSH_PAIR != 0
+      && matches!(
+        (left, right),
+        ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(')
+      )
+    {
+      return Some('|');
+    }
+
+    if rules & SMUSH_BIG_X != 0 {
+      match (left, right) {
+        ('/', '\\') => return Some('|'),
+        ('\\', '/') => return Some('Y'),
+        ('>', '<') => return Some('X'),
+        _ => {}
+      }
+    }
+
+    None
+  }
+}
+
+// Where figlet installs its fonts: under $PREFIX on Termux, /usr or /usr/local elsewhere
+fn font_dirs() -> Vec<PathBuf> {
+  let mut dirs = Vec::new();
+  if let Some(prefix) = env::var_os("PREFIX").filter(|prefix| !prefix.is_empty()) {
+    dirs.push(PathBuf::from(prefix).join("share/figlet"));
+  }
+  dirs.push(PathBuf::from("/usr/share/figlet"));
+  dirs.push(PathBuf::from("/usr/local/share/figlet"));
+  dirs
+}
+
+// full_layout supersedes old_layout: -1 full width, 0 fitting, otherwise smushing rules
+fn layout(
+  old_layout: i64,
+  full_layout: Option<i64>,
+) -> Layout {
+  match full_layout {
+    Some(full_layout) => {
+      let full_layout = full_layout as u32;
+      if full_layout & SMUSHING != 0 {
+        Layout::Smushing(full_layout & 63)
+      } else if full_layout & FITTING != 0 {
+        Layout::Fitting
+      } else {
+        Layout::FullWidth
+      }
+    }
+    None => match old_layout {
+      ..0 => Layout::FullWidth,
+      0 => Layout::Fitting,
+      rules => Layout::Smushing(rules as u32 & 63),
+    },
+  }
+}
+
+// One character: `height` lines, each ending in one or two end marks (usually @) that are
+// stripped along with trailing whitespace
+fn read_glyph<'a>(
+  lines: &mut impl Iterator<Item = &'a str>,
+  height: usize,
+) -> Option<Glyph> {
+  let mut glyph = Vec::with_capacity(height);
+  for _ in 0..height {
+    let line = lines.next()?.trim_end();
+    let line = match line.chars().last() {
+      Some(end_mark) => line.trim_end_matches(end_mark),
+      None => line,
+    };
+    glyph.push(line.chars().collect());
+  }
+  Some(glyph)
+}
+
+fn glyph_width(glyph: &[Vec<char>]) -> usize {
+  glyph.first().map_or(0, Vec::len)
+}
+
+fn parse_code(code: &str) -> Option<i64> {
+  let (negative, code) = match code.strip_prefix('-') {
+    Some(code) => (true, code),
+    None => (false, code),
+  };
+  let value = if let Some(hex) = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+    i64::from_str_radix(hex, 16).ok()?
+  } else if code.len() > 1 && code.starts_with('0') {
+    i64::from_str_radix(&code[1..], 8).ok()?
+  } else {
+    code.parse().ok()?
+  };
+  Some(if negative { -value } else { value })
+}
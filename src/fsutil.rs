@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Contents of a one-value file such as a sysfs attribute, None when missing or empty
+pub fn read_trimmed(path: &Path) -> Option<String> {
+  fs::read_to_string(path)
+    .ok()
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+}
+
+// Our directory under an XDG base directory: `$<variable>/rusty-motd`, or
+// `$HOME/<default>/rusty-motd` when the variable is unset or empty
+pub fn xdg_dir(
+  variable: &str,
+  default: &str,
+) -> Option<PathBuf> {
+  env::var_os(variable)
+    .filter(|dir| !dir.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(default)))
+    .map(|dir| dir.join("rusty-motd"))
+}
+
+// Replace `path` through a temporary file so a concurrent run never reads a partial file
+pub fn atomic_write(
+  path: &Path,
+  contents: &[u8],
+) -> io::Result<()> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+
+  let mut temp_path = path.as_os_str().to_owned();
+  temp_path.push(format!(".{}", std::process::id()));
+  fs::write(&temp_path, contents)?;
+  fs::rename(&temp_path, path).inspect_err(|_| {
+    let _ = fs::remove_file(&temp_path);
+  })
+}
@@ -0,0 +1,48 @@
+// Minimal shell-style glob matching for config filters: `*` matches any run of characters
+// (including `/`) and `?` matches exactly one character
+pub fn glob_match(
+  pattern: &str,
+  text: &str,
+) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let text: Vec<char> = text.chars().collect();
+
+  let (mut p, mut t) = (0, 0);
+  // Position of the last `*` and the text index it is currently matched up to
+  let mut backtrack: Option<(usize, usize)> = None;
+
+  while t < text.len() {
+    match pattern.get(p) {
+      Some('*') => {
+        backtrack = Some((p, t));
+        p += 1;
+      }
+      Some('?') => {
+        p += 1;
+        t += 1;
+      }
+      Some(&c) if c == text[t] => {
+        p += 1;
+        t += 1;
+      }
+      _ => match backtrack {
+        // Let the last `*` swallow one more character and retry
+        Some((star_p, star_t)) => {
+          p = star_p + 1;
+          t = star_t + 1;
+          backtrack = Some((star_p, star_t + 1));
+        }
+        None => return false,
+      },
+    }
+  }
+
+  pattern[p..].iter().all(|&c| c == '*')
+}
+
+pub fn matches_any(
+  patterns: &[String],
+  text: &str,
+) -> bool {
+  patterns.iter().any(|pattern| glob_match(pattern, text))
+}
@@ -0,0 +1,329 @@
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Cursor;
+use std::time::Duration;
+
+use zune_jpeg::JpegDecoder;
+use zune_jpeg::zune_core::bytestream::ZCursor;
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+use zune_jpeg::zune_core::options::DecoderOptions;
+
+use crate::cache;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+
+pub struct ImageLogo;
+
+// The cache key changes with the image and the size, so the TTL only decides when renders of
+// old images or sizes are dropped from the cache file
+const RENDER_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8";
+
+// Pixels more transparent than this show the terminal background
+const ALPHA_THRESHOLD: u32 = 128;
+
+type Rgb = (u8, u8, u8);
+
+// Decoded pixels, row by row, as RGBA
+struct Image {
+  width: usize,
+  height: usize,
+  pixels: Vec<[u8; 4]>,
+}
+
+impl Module for ImageLogo {
+  fn name(&self) -> &'static str {
+    "image-logo"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('i')
+  }
+
+  fn category(&self) -> Category {
+    Category::Branding
+  }
+
+  fn description(&self) -> &'static str {
+    "PNG or JPEG logo drawn with half-blocks"
+  }
+
+  fn is_logo(&self) -> bool {
+    true
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.image-logo] table)
+  let file: String = options
+    .get("file")
+    .ok_or_else(|| ModuleError::Failed("No image file set in [options.image-logo]".to_string()))?;
+  let width: usize = options.get("width").unwrap_or(32);
+  let dither: bool = options.get("dither").unwrap_or(true);
+
+  // Half-blocks without colors are just a solid shape
+  if style::theme().reset.is_empty() {
+    return Ok(Output::text(String::new()));
+  }
+
+  let bytes = fs::read(&file)
+    .map_err(|err| ModuleError::Failed(format!("Cannot read image {}: {}", file, err)))?;
+
+  let truecolor = style::truecolor_supported();
+  let palette = match (truecolor, dither) {
+    (true, _) => "truecolor",
+    (false, true) => "256-dithered",
+    (false, false) => "256",
+  };
+  let mut hasher = DefaultHasher::new();
+  bytes.hash(&mut hasher);
+  let key = format!("{:016x}-{}-{}", hasher.finish(), width, palette);
+
+  let mut error = None;
+  let text = cache::cached("image-logo", &key, RENDER_TTL, || {
+    decode(&bytes)
+      .map(|image| render(&scale(&image, width.max(1)), truecolor, dither))
+      .map_err(|err| error = Some(err))
+      .ok()
+  })
+  .ok_or_else(|| {
+    ModuleError::Failed(format!(
+      "Invalid image {}: {}",
+      file,
+      error.unwrap_or_default()
+    ))
+  })?;
+
+  Ok(Output::text(text))
+}
+
+fn decode(bytes: &[u8]) -> Result<Image, String> {
+  if bytes.starts_with(PNG_SIGNATURE) {
+    decode_png(bytes)
+  } else if bytes.starts_with(JPEG_SIGNATURE) {
+    decode_jpeg(bytes)
+  } else {
+    Err("not a PNG or JPEG file".to_string())
+  }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Image, String> {
+  let mut decoder = png::Decoder::new(Cursor::new(bytes));
+  // Palettes expanded, 16-bit channels reduced to 8 bits
+  decoder.set_transformations(png::Transformations::normalize_to_color8());
+  let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+  let mut buffer = vec![0; reader.output_buffer_size().ok_or("image too large")?];
+  let info = reader
+    .next_frame(&mut buffer)
+    .map_err(|err| err.to_string())?;
+
+  let to_rgba: fn(&[u8]) -> [u8; 4] = match info.color_type {
+    png::ColorType::Grayscale => |p| [p[0], p[0], p[0], 255],
+    png::ColorType::GrayscaleAlpha => |p| [p[0], p[0], p[0], p[1]],
+    png::ColorType::Rgb => |p| [p[0], p[1], p[2], 255],
+    png::ColorType::Rgba => |p| [p[0], p[1], p[2], p[3]],
+    png::ColorType::Indexed => return Err("unexpanded palette".to_string()),
+  };
+  let channels = info.color_type.samples();
+  let (width, height) = (info.width as usize, info.height as usize);
+
+  let pixels = buffer
+    .chunks(info.line_size)
+    .take(height)
+    .flat_map(|line| line[..width * channels].chunks(channels).map(to_rgba))
+    .collect();
+  Ok(Image {
+    width,
+    height,
+    pixels,
+  })
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<Image, String> {
+  let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGB);
+  let mut decoder = JpegDecoder::new_with_options(ZCursor::new(bytes), options);
+  let rgb = decoder.decode().map_err(|err| err.to_string())?;
+  let (width, height) = decoder.dimensions().ok_or("missing image size")?;
+
+  let pixels = rgb
+    .chunks_exact(3)
+    .map(|p| [p[0], p[1], p[2], 255])
+    .collect();
+  Ok(Image {
+    width,
+    height,
+    pixels,
+  })
+}
+
+// Resize to `width` columns by averaging the source pixels each target pixel covers. A cell
+// holds two pixels stacked and is about twice as tall as wide, so pixels stay square.
+fn scale(
+  image: &Image,
+  width: usize,
+) -> Image {
+  let height = (image.height * width).div_ceil(image.width.max(1)).max(1);
+  // Whole cells only
+  let height = height + height % 2;
+
+  let span = |target: usize, target_len: usize, source_len: usize| {
+    let start = target * source_len / target_len;
+    let end = ((target + 1) * source_len / target_len).max(start + 1);
+    start.min(source_len.saturating_sub(1))..end.min(source_len)
+  };
+
+  let mut pixels = Vec::with_capacity(width * height);
+  for y in 0..height {
+    let rows = span(y, height, image.height);
+    for x in 0..width {
+      let columns = span(x, width, image.width);
+
+      // Colors weighted by alpha, so transparent pixels do not darken the edges. The sums are
+      // u64 since a cell of a large photo can cover tens of thousands of pixels.
+      let mut sums = [0u64; 4];
+      let mut count = 0;
+      for row in rows.clone() {
+        for &[r, g, b, a] in &image.pixels[row * image.width + columns.start..][..columns.len()] {
+          let a = a as u64;
+          sums[0] += r as u64 * a;
+          sums[1] += g as u64 * a;
+          sums[2] += b as u64 * a;
+          sums[3] += a;
+          count += 1;
+        }
+      }
+
+      let alpha = sums[3] / count.max(1);
+      let channel = |sum: u64| (sum / sums[3].max(1)) as u8;
+      pixels.push([
+        channel(sums[0]),
+        channel(sums[1]),
+        channel(sums[2]),
+        alpha as u8,
+      ]);
+    }
+  }
+
+  Image {
+    width,
+    height,
+    pixels,
+  }
+}
+
+// Upper half-blocks with the top pixel as foreground and the bottom one as background
+fn render(
+  image: &Image,
+  truecolor: bool,
+  dither: bool,
+) -> String {
+  let opaque: Vec<Option<Rgb>> = image
+    .pixels
+    .iter()
+    .map(|&[r, g, b, a]| (a as u32 >= ALPHA_THRESHOLD).then_some((r, g, b)))
+    .collect();
+
+  // SGR color parameters of each pixel, after "38;" or "48;"
+  let colors: Vec<Option<String>> = if truecolor {
+    opaque
+      .iter()
+      .map(|pixel| pixel.map(|(r, g, b)| format!("2;{};{};{}", r, g, b)))
+      .collect()
+  } else {
+    let indexes = if dither {
+      floyd_steinberg(&opaque, image.width)
+    } else {
+      opaque
+        .iter()
+        .map(|pixel| pixel.map(|(r, g, b)| style::rgb_to_ansi256(r, g, b)))
+        .collect()
+    };
+    indexes
+      .iter()
+      .map(|index| index.map(|index| format!("5;{}", index)))
+      .collect()
+  };
+
+  let reset = &style::theme().reset;
+  let mut output = String::new();
+  for y in (0..image.height).step_by(2) {
+    // Every row starts and ends without colors, so blank cells need no escape
+    let mut current = "0".to_string();
+    for x in 0..image.width {
+      let top = &colors[y * image.width + x];
+      let bottom = &colors[(y + 1) * image.width + x];
+      let (block, params) = match (top, bottom) {
+        (None, None) => (' ', "0".to_string()),
+        (Some(top), None) => ('▀', format!("0;38;{}", top)),
+        (None, Some(bottom)) => ('▄', format!("0;38;{}", bottom)),
+        (Some(top), Some(bottom)) => ('▀', format!("0;38;{};48;{}", top, bottom)),
+      };
+      if current != params {
+        output.push_str(&format!("\x1B[{}m", params));
+        current = params;
+      }
+      output.push(block);
+    }
+    if current != "0" {
+      output.push_str(reset);
+    }
+    output.push('\n');
+  }
+  output
+}
+
+// Nearest 256-color palette entries, spreading each pixel's rounding error over its unvisited
+// neighbours so gradients keep their average color instead of banding
+fn floyd_steinberg(
+  pixels: &[Option<Rgb>],
+  width: usize,
+) -> Vec<Option<u8>> {
+  let mut work: Vec<[f32; 3]> = pixels
+    .iter()
+    .map(|pixel| pixel.map_or([0.0; 3], |(r, g, b)| [r as f32, g as f32, b as f32]))
+    .collect();
+  let mut indexes = vec![None; pixels.len()];
+
+  for i in 0..pixels.len() {
+    if pixels[i].is_none() {
+      continue;
+    }
+    let [r, g, b] = work[i].map(|channel| channel.clamp(0.0, 255.0) as u8);
+    let index = style::rgb_to_ansi256(r, g, b);
+    indexes[i] = Some(index);
+
+    let (pr, pg, pb) = style::ansi256_to_rgb(index);
+    let error = [
+      work[i][0] - pr as f32,
+      work[i][1] - pg as f32,
+      work[i][2] - pb as f32,
+    ];
+
+    let (x, y) = (i % width, i / width);
+    let neighbours = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+    for (dx, dy, weight) in neighbours {
+      let Some(nx) = x.checked_add_signed(dx).filter(|&nx| nx < width) else {
+        continue;
+      };
+      let neighbour = (y + dy) * width + nx;
+      if pixels.get(neighbour).is_some_and(Option::is_some) {
+        for (channel, error) in work[neighbour].iter_mut().zip(error) {
+          *channel += error * weight / 16.0;
+        }
+      }
+    }
+  }
+
+  indexes
+}
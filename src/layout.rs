@@ -0,0 +1,143 @@
+use std::env;
+
+use serde::Deserialize;
+use unicode_width::UnicodeWidthChar;
+
+use crate::style;
+
+// Space between the logo column and the info column
+const COLUMN_GAP: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+  #[default]
+  Stacked,
+  SideBySide,
+}
+
+// Place `left` (a logo) next to `right` (everything else), falling back to stacking
+// the two when the terminal is too narrow to fit both columns.
+pub fn side_by_side(
+  left: &str,
+  right: &str,
+) -> String {
+  let mut left_lines = carry_styles(left.trim_matches('\n'));
+  while left_lines
+    .last()
+    .is_some_and(|line| visible_width(line) == 0)
+  {
+    left_lines.pop();
+  }
+  let right_lines = carry_styles(right.trim_start_matches('\n'));
+
+  let left_width = left_lines
+    .iter()
+    .map(|line| visible_width(line))
+    .max()
+    .unwrap_or(0);
+  let right_width = right_lines
+    .iter()
+    .map(|line| visible_width(line))
+    .max()
+    .unwrap_or(0);
+
+  if left_width + COLUMN_GAP + right_width > terminal_width() {
+    return format!("{}{}", left, right);
+  }
+
+  let mut output = String::new();
+  for row in 0..left_lines.len().max(right_lines.len()) {
+    let left_line = left_lines.get(row).map_or("", String::as_str);
+    let padding = left_width - visible_width(left_line) + COLUMN_GAP;
+
+    output.push_str(left_line);
+    output.push_str(&" ".repeat(padding));
+    output.push_str(right_lines.get(row).map_or("", String::as_str));
+    output.push('\n');
+  }
+
+  output
+}
+
+// Display width of a string, ignoring ANSI escape sequences and counting wide glyphs as two columns
+pub fn visible_width(text: &str) -> usize {
+  let mut width = 0;
+  let mut chars = text.chars();
+
+  while let Some(c) = chars.next() {
+    if c == '\x1B' {
+      skip_escape(&mut chars);
+    } else {
+      width += c.width().unwrap_or(0);
+    }
+  }
+
+  width
+}
+
+// Consume the rest of an escape sequence after ESC (CSI sequences end with a byte in @..~)
+fn skip_escape(chars: &mut std::str::Chars) {
+  if chars.next() == Some('[') {
+    for c in chars.by_ref() {
+      if ('@'..='~').contains(&c) {
+        break;
+      }
+    }
+  }
+}
+
+// Split text into lines that each restore the colors active at the start of the line and
+// reset them at the end, so the lines can be interleaved with other text.
+fn carry_styles(text: &str) -> Vec<String> {
+  let mut active = String::new();
+  let mut lines = Vec::new();
+
+  for line in text.lines() {
+    let mut styled = active.clone();
+    styled.push_str(line);
+    styled.push_str(&style::theme().reset);
+    lines.push(styled);
+
+    // Track SGR sequences so far; a reset clears everything before it
+    let mut rest = line;
+    while let Some(start) = rest.find("\x1B[") {
+      let sequence = &rest[start..];
+      let Some(end) = sequence[2..].find(|c: char| ('@'..='~').contains(&c)) else {
+        break;
+      };
+      let end = end + 2;
+      let escape = &sequence[..=end];
+      if escape.ends_with('m') {
+        // Parameters starting with 0 (or none at all) reset everything before them
+        let params = &escape[2..escape.len() - 1];
+        if params.is_empty() || params == "0" {
+          active.clear();
+        } else if params.starts_with("0;") {
+          active = escape.to_string();
+        } else {
+          active.push_str(escape);
+        }
+      }
+      rest = &sequence[end + 1..];
+    }
+  }
+
+  lines
+}
+
+// Width of the terminal attached to stdout, then $COLUMNS, then a conservative default
+pub fn terminal_width() -> usize {
+  // SAFETY: winsize is plain old data filled in by the ioctl
+  let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+  if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0
+    && size.ws_col > 0
+  {
+    return size.ws_col as usize;
+  }
+
+  env::var("COLUMNS")
+    .ok()
+    .and_then(|columns| columns.parse().ok())
+    .unwrap_or(80)
+}
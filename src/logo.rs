@@ -0,0 +1,271 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::config::ModuleOptions;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style::{self, ColorSpec};
+
+pub struct Logo;
+
+// ${c1} to ${c6}, as in neofetch logos
+const COLOR_SLOTS: usize = 6;
+
+// A bundled logo in two sizes with its brand colors (empty: the theme's logo colors)
+pub struct Distro {
+  pub id: &'static str,
+  pub large: &'static str,
+  pub small: &'static str,
+  pub colors: &'static [&'static str],
+}
+
+// Matched against the os-release ID, then each ID_LIKE entry
+const DISTROS: &[Distro] = &[
+  Distro {
+    id: "android",
+    large: include_str!("../logos/android.txt"),
+    small: include_str!("../logos/android-small.txt"),
+    colors: &[],
+  },
+  Distro {
+    id: "alpine",
+    large: include_str!("../logos/alpine.txt"),
+    small: include_str!("../logos/alpine-small.txt"),
+    colors: &["blue", "bright-blue"],
+  },
+  Distro {
+    id: "arch",
+    large: include_str!("../logos/arch.txt"),
+    small: include_str!("../logos/arch-small.txt"),
+    colors: &["bright-cyan", "cyan"],
+  },
+  Distro {
+    id: "debian",
+    large: include_str!("../logos/debian.txt"),
+    small: include_str!("../logos/debian-small.txt"),
+    colors: &["bold red", "bright-white"],
+  },
+  Distro {
+    id: "fedora",
+    large: include_str!("../logos/fedora.txt"),
+    small: include_str!("../logos/fedora-small.txt"),
+    colors: &["#51a2da", "bright-white"],
+  },
+  Distro {
+    id: "gentoo",
+    large: include_str!("../logos/gentoo.txt"),
+    small: include_str!("../logos/gentoo-small.txt"),
+    colors: &["magenta", "bright-white"],
+  },
+  Distro {
+    id: "manjaro",
+    large: include_str!("../logos/manjaro.txt"),
+    small: include_str!("../logos/manjaro-small.txt"),
+    colors: &["bright-green"],
+  },
+  Distro {
+    id: "nixos",
+    large: include_str!("../logos/nixos.txt"),
+    small: include_str!("../logos/nixos-small.txt"),
+    colors: &["blue", "bright-cyan"],
+  },
+  Distro {
+    id: "ubuntu",
+    large: include_str!("../logos/ubuntu.txt"),
+    small: include_str!("../logos/ubuntu-small.txt"),
+    colors: &["#e95420", "bright-white"],
+  },
+  // Tux, for everything else
+  Distro {
+    id: "linux",
+    large: include_str!("../logos/linux.txt"),
+    small: include_str!("../logos/linux-small.txt"),
+    colors: &["bright-white", "bright-black", "yellow"],
+  },
+];
+
+const OS_RELEASE_PATHS: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+impl Module for Logo {
+  fn name(&self) -> &'static str {
+    "logo"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('g')
+  }
+
+  fn category(&self) -> Category {
+    Category::Branding
+  }
+
+  fn description(&self) -> &'static str {
+    "ASCII logo from a file with ${c1}..${c6} color tokens"
+  }
+
+  fn is_logo(&self) -> bool {
+    true
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    // Configuration (overridable from the [options.logo] table)
+    let file: String = ctx
+      .options
+      .get("file")
+      .ok_or_else(|| ModuleError::Failed("No logo file set in [options.logo]".to_string()))?;
+
+    let art = load(Path::new(&file)).map_err(ModuleError::Failed)?;
+    Ok(Output::text(render(&art, &palette(&ctx.options, &[])?)))
+  }
+}
+
+// The logo picked by the `distro` option: "auto" (default) detects the running system,
+// any other value names a bundled logo
+pub fn distro(options: &ModuleOptions) -> Result<&'static Distro, ModuleError> {
+  let id: String = options.get("distro").unwrap_or_else(|| "auto".to_string());
+  if id == "auto" {
+    return Ok(detect());
+  }
+  find(&id).ok_or_else(|| ModuleError::Failed(format!("Unknown logo distro: {}", id)))
+}
+
+fn find(id: &str) -> Option<&'static Distro> {
+  DISTROS.iter().find(|distro| distro.id == id)
+}
+
+// Android first, since proot containers on it often carry another distro's os-release
+fn detect() -> &'static Distro {
+  let fallback = &DISTROS[DISTROS.len() - 1];
+  if is_android() {
+    return find("android").unwrap_or(fallback);
+  }
+  os_release_ids()
+    .iter()
+    .find_map(|id| find(id))
+    .unwrap_or(fallback)
+}
+
+pub fn is_android() -> bool {
+  env::var_os("TERMUX_VERSION").is_some() || Path::new("/system/build.prop").exists()
+}
+
+// ID followed by the ID_LIKE entries, e.g. ["linuxmint", "ubuntu", "debian"]
+fn os_release_ids() -> Vec<String> {
+  let values = os_release_values(&["ID", "ID_LIKE"]);
+  values
+    .iter()
+    .flatten()
+    .flat_map(|value| value.split_whitespace())
+    .map(str::to_lowercase)
+    .collect()
+}
+
+// Display name of the running system, e.g. "Debian GNU/Linux 12 (bookworm)"
+pub fn os_release_name() -> Option<String> {
+  os_release_values(&["PRETTY_NAME", "NAME"])
+    .into_iter()
+    .flatten()
+    .find(|name| !name.is_empty())
+}
+
+// The values of `keys` in the first os-release file found, unquoted
+fn os_release_values(keys: &[&str]) -> Vec<Option<String>> {
+  let contents = OS_RELEASE_PATHS
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .unwrap_or_default();
+
+  keys
+    .iter()
+    .map(|key| {
+      contents.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix('=')?;
+        Some(value.trim().trim_matches(['"', '\'']).to_string())
+      })
+    })
+    .collect()
+}
+
+pub fn load(path: &Path) -> Result<String, String> {
+  fs::read_to_string(path).map_err(|err| format!("Cannot read logo {}: {}", path.display(), err))
+}
+
+// Escape sequences for ${c1}..${c6}: the theme's logo colors, accent, label, warn and critical,
+// overridden by a logo's own `defaults` and then by the `colors` option (theme color specs)
+pub fn palette(
+  options: &ModuleOptions,
+  defaults: &[&str],
+) -> Result<Vec<String>, ModuleError> {
+  let theme = style::theme();
+  let mut palette = vec![
+    theme.logo_primary.clone(),
+    theme.logo_secondary.clone(),
+    theme.accent.clone(),
+    theme.label.clone(),
+    theme.warn.clone(),
+    theme.critical.clone(),
+  ];
+
+  // Without color the theme is empty and the art is printed plain
+  if theme.reset.is_empty() {
+    return Ok(palette);
+  }
+
+  let mut specs: Vec<ColorSpec> = defaults
+    .iter()
+    .map(|spec| ColorSpec::Text(spec.to_string()))
+    .collect();
+  let colors: Vec<ColorSpec> = options.get("colors").unwrap_or_default();
+  for (index, color) in colors.into_iter().enumerate() {
+    match specs.get_mut(index) {
+      Some(spec) => *spec = color,
+      None => specs.push(color),
+    }
+  }
+
+  for (slot, spec) in palette.iter_mut().zip(&specs) {
+    *slot =
+      style::parse_color(spec).map_err(|err| ModuleError::Failed(format!("Logo: {}", err)))?;
+  }
+  Ok(palette)
+}
+
+// Replace the color tokens of `art`. Text before the first token is drawn in ${c1}, and the
+// result ends in exactly one newline so every logo is spaced the same.
+pub fn render(
+  art: &str,
+  palette: &[String],
+) -> String {
+  let mut output = palette.first().cloned().unwrap_or_default();
+  let mut rest = art.trim_end_matches(['\n', '\r']);
+
+  while let Some(start) = rest.find("${c") {
+    output.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    let slot = rest
+      .get(3..5)
+      .filter(|token| token.ends_with('}'))
+      .and_then(|token| token[..1].parse::<usize>().ok())
+      .filter(|slot| (1..=COLOR_SLOTS).contains(slot));
+    match slot {
+      Some(slot) => {
+        output.push_str(palette.get(slot - 1).map_or("", String::as_str));
+        rest = &rest[5..];
+      }
+      // Not a color token, keep it as art
+      None => {
+        output.push_str("${c");
+        rest = &rest[3..];
+      }
+    }
+  }
+
+  output.push_str(rest);
+  output.push_str(&style::theme().reset);
+  output.push('\n');
+  output
+}
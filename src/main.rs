@@ -1,203 +1,209 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::env;
 use std::io::{self, Write};
-use std::sync::{Arc, Mutex};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{Map, Value, json};
 
 // Import all your module files
 mod android_logo;
 mod android_logo_small;
 mod android_temp;
+mod bar;
+mod battery;
+mod cache;
+mod config;
 mod disk_space;
+mod figlet;
+mod fsutil;
+mod glob;
+mod image_logo;
+mod layout;
+mod logo;
+mod meminfo;
+mod memory;
+mod module;
+mod network;
+mod ports;
+mod registry;
+mod sensors;
+mod style;
 mod sysinfo;
+mod temperature;
 mod termux_banner;
+mod units;
 mod uptime;
+mod uptime_records;
+mod wifi;
 
-// Default enabled modules
-const DEFAULT_MODULES: &[&str] = &[
-  "android-logo-small",
-  "sysinfo",
-  "android-temp",
-  "disk-space",
-];
+use cache::CacheMode;
+use config::{Config, OutputFormat};
+use layout::Layout;
+use module::{Context, Module, ModuleError, Output};
 
-struct Config {
-  enabled_modules: HashSet<String>,
-}
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(30);
 
-impl Config {
-  fn new() -> Self {
-    let mut enabled_modules = HashSet::new();
+fn main() -> io::Result<()> {
+  let mut config = Config::new();
+  config.parse_args();
 
-    // Enable default modules if no args provided
-    let args: Vec<String> = env::args().collect();
-    if args.len() == 1 {
-      for module in DEFAULT_MODULES {
-        enabled_modules.insert(module.to_string());
-      }
-    }
+  let enabled_modules = config.get_enabled_modules();
+  cache::init(config.cache);
+  style::init(config.color, &config.theme, &config.themes);
 
-    Config { enabled_modules }
+  // The background cache refresh has no one waiting on it, so give modules all the time they need
+  let refreshing = config.cache == CacheMode::Refresh;
+  if refreshing {
+    config.timeout = REFRESH_TIMEOUT;
   }
 
-  fn parse_args(&mut self) {
-    let args: Vec<String> = env::args().collect();
-
-    // If flags are provided, clear defaults and only use specified modules
-    if args.len() > 1 {
-      self.enabled_modules.clear();
-    }
-
-    for arg in &args[1..] {
-      match arg.as_str() {
-        "-l" => {
-          self
-            .enabled_modules
-            .insert("android-logo-small".to_string());
-        }
-        "-L" => {
-          self.enabled_modules.insert("android-logo".to_string());
-        }
-        "-b" => {
-          self.enabled_modules.insert("termux-banner".to_string());
-        }
-        "-s" => {
-          self.enabled_modules.insert("sysinfo".to_string());
-        }
-        "-u" => {
-          self.enabled_modules.insert("uptime".to_string());
-        }
-        "-t" => {
-          self.enabled_modules.insert("android-temp".to_string());
-        }
-        "-d" => {
-          self.enabled_modules.insert("disk-space".to_string());
-        }
-        "-h" | "--help" => {
-          self.show_help();
-          std::process::exit(0);
-        }
-        _ => {
-          eprintln!("Unknown flag: {}", arg);
-          eprintln!("Use -h or --help for usage information");
-          std::process::exit(1);
-        }
-      }
-    }
+  if enabled_modules.is_empty() {
+    return Ok(());
   }
 
-  fn show_help(&self) {
-    println!("System Information Display");
-    println!();
-    println!(
-      "Usage: {} [FLAGS]",
-      env::args().next().unwrap_or_else(|| "program".to_string())
-    );
-    println!();
-    println!("Available modules:");
-    println!("  -l    Android logo (small)");
-    println!("  -L    Android logo (big)");
-    println!("  -b    Termux banner");
-    println!("  -s    System information");
-    println!("  -u    Uptime");
-    println!("  -t    Android temperature");
-    println!("  -d    Disk space");
-    println!();
-    println!("  -h, --help    Show this help message");
-    println!();
-    println!(
-      "Default modules (when no flags provided): android-logo-small, sysinfo, android-temp, disk-space"
-    );
-  }
+  // Modules report back over a channel so slow ones can be abandoned at their deadline
+  let (sender, receiver) = mpsc::channel();
+  let start = Instant::now();
+  let mut deadlines = HashMap::new();
 
-  fn get_enabled_modules(&self) -> Vec<String> {
-    // Return modules in categorical order
-    let module_categories = [
-      // Display/Branding (always first)
-      vec!["android-logo-small", "android-logo", "termux-banner"],
-      // System Info (second)
-      vec!["sysinfo", "uptime"],
-      // Temperature (third)
-      vec!["android-temp"],
-      // Storage (fourth)
-      vec!["disk-space"],
-    ];
-
-    let mut ordered_modules = Vec::new();
-
-    for category in &module_categories {
-      for &module in category {
-        if self.enabled_modules.contains(module) {
-          ordered_modules.push(module.to_string());
+  // Execute modules in parallel
+  for &module in &enabled_modules {
+    let ctx = Context {
+      options: config.module_options(module.name()),
+      temperature_unit: config.temperature_unit,
+    };
+
+    // A module timeout can only shorten the overall render budget
+    let timeout = ctx
+      .options
+      .get::<u64>("timeout_ms")
+      .map_or(config.timeout, Duration::from_millis)
+      .min(config.timeout);
+    deadlines.insert(module.name(), start + timeout);
+
+    let sender = sender.clone();
+    thread::spawn(move || {
+      // A panic is reported like any other failure, so the loop below does not wait for it
+      let output = panic::catch_unwind(AssertUnwindSafe(|| module.render(&ctx)))
+        .unwrap_or_else(|_| Err(ModuleError::Failed("panicked".to_string())));
+      let _ = sender.send((module.name(), output));
+    });
+  }
+  drop(sender);
+
+  // Collect results until every module has reported or run out of time. Threads of
+  // modules that timed out are left behind and die with the process.
+  let mut output_map = HashMap::new();
+  while !deadlines.is_empty() {
+    let next_deadline = *deadlines.values().min().unwrap();
+    let wait = next_deadline.saturating_duration_since(Instant::now());
+
+    match receiver.recv_timeout(wait) {
+      Ok((name, output)) => {
+        if deadlines.remove(name).is_some() {
+          output_map.insert(name, output);
         }
       }
+      Err(RecvTimeoutError::Timeout) => {
+        let now = Instant::now();
+        deadlines.retain(|&name, &mut deadline| {
+          if deadline <= now {
+            output_map.insert(name, Err(ModuleError::TimedOut));
+            return false;
+          }
+          true
+        });
+      }
+      // Every thread has finished
+      Err(RecvTimeoutError::Disconnected) => break,
     }
-
-    ordered_modules
   }
-}
-
-fn main() -> io::Result<()> {
-  let mut config = Config::new();
-  config.parse_args();
-
-  let enabled_modules = config.get_enabled_modules();
 
-  if enabled_modules.is_empty() {
+  if refreshing {
     return Ok(());
   }
 
-  // Create thread-safe output collection
-  let output_map = Arc::new(Mutex::new(HashMap::new()));
-  let mut handles = Vec::new();
+  match config.format {
+    OutputFormat::Text => print_text(&enabled_modules, &output_map, config.layout),
+    OutputFormat::Json => print_json(&enabled_modules, &output_map),
+  }
 
-  // Execute modules in parallel
-  for module_name in &enabled_modules {
-    let module_name = module_name.clone();
-    let output_map = Arc::clone(&output_map);
-
-    let handle = thread::spawn(move || {
-      let output = match module_name.as_str() {
-        "android-logo-small" => android_logo_small::run(),
-        "android-logo" => android_logo::run(),
-        "termux-banner" => termux_banner::run(),
-        "sysinfo" => sysinfo::run(),
-        "uptime" => uptime::run(),
-        "android-temp" => android_temp::run(),
-        "disk-space" => disk_space::run(),
-        _ => String::new(),
-      };
-
-      if !output.is_empty() {
-        let mut map = output_map.lock().unwrap();
-        map.insert(module_name, output);
-      }
-    });
+  io::stdout().flush()?;
+  cache::spawn_refresh_if_needed();
+  Ok(())
+}
 
-    handles.push(handle);
+fn print_text(
+  enabled_modules: &[&dyn Module],
+  output_map: &HashMap<&str, Result<Output, ModuleError>>,
+  layout: Layout,
+) {
+  let mut logo = None;
+  let mut body = String::new();
+
+  for module in enabled_modules {
+    let text = match output_map.get(module.name()) {
+      Some(Ok(output)) => output.text.clone(),
+      Some(Err(err)) => render_error(*module, err),
+      None => continue,
+    };
+
+    // The first logo gets its own column in the side-by-side layout
+    if layout == Layout::SideBySide && module.is_logo() && logo.is_none() {
+      logo = Some(text);
+    } else {
+      body.push_str(&text);
+    }
   }
 
-  // Wait for all threads to complete
-  for handle in handles {
-    let _ = handle.join();
+  // Clear screen (only on an interactive terminal) and display all collected output in order
+  if style::stdout_is_terminal() {
+    print!("\x1B[2J\x1B[1;1H");
   }
 
-  // Get the output map
-  let output_map = match Arc::try_unwrap(output_map) {
-    Ok(mutex) => mutex.into_inner().unwrap(),
-    Err(_) => HashMap::new(),
-  };
-
-  // Clear screen and display all collected output in order
-  print!("\x1B[2J\x1B[1;1H");
+  match logo {
+    Some(logo) => print!("{}", layout::side_by_side(&logo, &body)),
+    None => print!("{}", body),
+  }
+}
 
-  for module_name in &enabled_modules {
-    if let Some(output) = output_map.get(module_name) {
-      print!("{}", output);
-    }
+fn print_json(
+  enabled_modules: &[&dyn Module],
+  output_map: &HashMap<&str, Result<Output, ModuleError>>,
+) {
+  // One object keyed by module name, in display order. Modules without data (logos) are left out.
+  let mut document = Map::new();
+
+  for module in enabled_modules {
+    let value = match output_map.get(module.name()) {
+      Some(Ok(output)) if output.data.is_null() => continue,
+      Some(Ok(output)) => output.data.clone(),
+      Some(Err(err)) => json!({ "error": err.to_string() }),
+      None => continue,
+    };
+    document.insert(module.name().to_string(), value);
   }
 
-  io::stdout().flush()?;
-  Ok(())
+  println!(
+    "{}",
+    serde_json::to_string_pretty(&Value::Object(document)).unwrap_or_default()
+  );
+}
+
+fn render_error(
+  module: &dyn Module,
+  err: &ModuleError,
+) -> String {
+  let theme = style::theme();
+  format!(
+    "{}{}:{} {}{}{}\n",
+    theme.bold,
+    module.name(),
+    theme.reset,
+    theme.critical,
+    err,
+    theme.reset
+  )
 }
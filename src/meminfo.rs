@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+use std::fs;
+
+// Parsed /proc/meminfo, keyed by field name without the colon ("MemTotal", "SwapFree")
+pub struct MemInfo(HashMap<String, u64>);
+
+impl MemInfo {
+  pub fn read() -> Option<Self> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let fields = meminfo
+      .lines()
+      .filter_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let kb = value.split_whitespace().next()?.parse().ok()?;
+        Some((name.to_string(), kb))
+      })
+      .collect();
+    Some(MemInfo(fields))
+  }
+
+  // Values are reported in kB
+  pub fn bytes(
+    &self,
+    field: &str,
+  ) -> Option<u64> {
+    self.0.get(field).map(|kb| kb * 1024)
+  }
+}
@@ -0,0 +1,218 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::bar;
+use crate::meminfo::MemInfo;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::units::format_bytes_binary;
+
+pub struct Memory;
+
+const BLOCK_DIR: &str = "/sys/block";
+
+#[derive(Serialize)]
+struct MemoryData {
+  ram: Usage,
+  // Absent when no swap is configured
+  swap: Option<Usage>,
+  zram: Vec<Zram>,
+}
+
+#[derive(Serialize)]
+struct Usage {
+  used_bytes: u64,
+  available_bytes: u64,
+  total_bytes: u64,
+  usage_percent: u32,
+}
+
+#[derive(Serialize)]
+struct Zram {
+  device: String,
+  disk_size_bytes: u64,
+  // Uncompressed size of the data stored in the device
+  original_bytes: u64,
+  compressed_bytes: u64,
+  // RAM used by the device, including allocator overhead
+  memory_used_bytes: u64,
+  compression_ratio: Option<f64>,
+}
+
+impl Module for Memory {
+  fn name(&self) -> &'static str {
+    "memory"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('m')
+  }
+
+  fn category(&self) -> Category {
+    Category::System
+  }
+
+  fn description(&self) -> &'static str {
+    "RAM, swap and zram usage"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.memory] table)
+  let max_usage: u32 = options.get("max_usage").unwrap_or(90);
+  let alert_usage: u32 = options.get("alert_usage").unwrap_or(75);
+  let bar_width: usize = options.get("bar_width").unwrap_or(50);
+  let show_swap: bool = options.get("show_swap").unwrap_or(true);
+  let show_zram: bool = options.get("show_zram").unwrap_or(true);
+
+  let meminfo = MemInfo::read()
+    .ok_or_else(|| ModuleError::Failed("Error reading /proc/meminfo".to_string()))?;
+
+  let ram = meminfo
+    .bytes("MemTotal")
+    .zip(meminfo.bytes("MemAvailable"))
+    .map(|(total, available)| usage(total, available))
+    .ok_or_else(|| ModuleError::Failed("Error parsing /proc/meminfo".to_string()))?;
+
+  let swap = meminfo
+    .bytes("SwapTotal")
+    .zip(meminfo.bytes("SwapFree"))
+    .filter(|(total, _)| show_swap && *total > 0)
+    .map(|(total, free)| usage(total, free));
+
+  let zram = if show_zram {
+    zram_devices()
+  } else {
+    Vec::new()
+  };
+
+  let theme = style::theme();
+  let color = |usage: u32| {
+    if usage >= max_usage {
+      &theme.critical
+    } else if usage >= alert_usage {
+      &theme.warn
+    } else {
+      &theme.ok
+    }
+  };
+
+  let mut output = String::new();
+  output.push_str(&format!("\n{}Memory:{}\n", theme.bold, theme.reset));
+
+  for (name, usage) in [("RAM", Some(&ram)), ("Swap", swap.as_ref())] {
+    let Some(usage) = usage else {
+      continue;
+    };
+    output.push_str(&format!(
+      "  {:<8}{} used out of {} ({}%)\n",
+      name,
+      format_bytes_binary(usage.used_bytes),
+      format_bytes_binary(usage.total_bytes),
+      usage.usage_percent
+    ));
+    output.push_str(&format!(
+      "  {}\n",
+      bar::render(usage.usage_percent, bar_width, color(usage.usage_percent))
+    ));
+  }
+
+  for device in &zram {
+    let ratio = device
+      .compression_ratio
+      .map(|ratio| format!(" ({:.1}x)", ratio))
+      .unwrap_or_default();
+    output.push_str(&format!(
+      "  {:<8}{} stored in {}{}, {} disk\n",
+      device.device,
+      format_bytes_binary(device.original_bytes),
+      format_bytes_binary(device.memory_used_bytes),
+      ratio,
+      format_bytes_binary(device.disk_size_bytes)
+    ));
+  }
+
+  let data = MemoryData { ram, swap, zram };
+  Ok(Output::new(output, &data))
+}
+
+fn usage(
+  total: u64,
+  available: u64,
+) -> Usage {
+  let used = total.saturating_sub(available);
+  Usage {
+    used_bytes: used,
+    available_bytes: available,
+    total_bytes: total,
+    usage_percent: match total {
+      0 => 0,
+      total => (used * 100).div_ceil(total) as u32,
+    },
+  }
+}
+
+// Initialized zram devices. mm_stat holds: orig_data_size compr_data_size mem_used_total
+// mem_limit mem_used_max same_pages pages_compacted huge_pages
+fn zram_devices() -> Vec<Zram> {
+  let Ok(entries) = fs::read_dir(BLOCK_DIR) else {
+    return Vec::new();
+  };
+
+  let mut devices: Vec<Zram> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let device = entry.file_name().to_string_lossy().into_owned();
+      if !device.starts_with("zram") {
+        return None;
+      }
+      read_zram(&entry.path(), device)
+    })
+    .collect();
+
+  devices.sort_by(|a, b| a.device.cmp(&b.device));
+  devices
+}
+
+fn read_zram(
+  path: &Path,
+  device: String,
+) -> Option<Zram> {
+  let disk_size_bytes: u64 = fs::read_to_string(path.join("disksize"))
+    .ok()?
+    .trim()
+    .parse()
+    .ok()
+    .filter(|size| *size > 0)?;
+
+  let mm_stat = fs::read_to_string(path.join("mm_stat")).ok()?;
+  let stats: Vec<u64> = mm_stat
+    .split_whitespace()
+    .take(3)
+    .filter_map(|field| field.parse().ok())
+    .collect();
+  let [original_bytes, compressed_bytes, memory_used_bytes] = stats[..] else {
+    return None;
+  };
+
+  Some(Zram {
+    device,
+    disk_size_bytes,
+    original_bytes,
+    compressed_bytes,
+    memory_used_bytes,
+    compression_ratio: (compressed_bytes > 0)
+      .then(|| original_bytes as f64 / compressed_bytes as f64),
+  })
+}
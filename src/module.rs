@@ -0,0 +1,100 @@
+use std::fmt;
+use std::io;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::ModuleOptions;
+use crate::temperature::TemperatureUnit;
+
+// Display order of module groups when modules are picked with flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+  Branding,
+  System,
+  Temperature,
+  Power,
+  Storage,
+  Network,
+}
+
+// Everything a module gets to see while rendering
+pub struct Context {
+  pub options: ModuleOptions,
+  pub temperature_unit: TemperatureUnit,
+}
+
+// Rendered text for the terminal plus the typed data it was built from (used by --format json)
+pub struct Output {
+  pub text: String,
+  pub data: Value,
+}
+
+impl Output {
+  pub fn new(
+    text: String,
+    data: &impl Serialize,
+  ) -> Self {
+    Output {
+      text,
+      data: serde_json::to_value(data).unwrap_or(Value::Null),
+    }
+  }
+
+  // Output without structured data (logos and other decorations)
+  pub fn text(text: String) -> Self {
+    Output {
+      text,
+      data: Value::Null,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum ModuleError {
+  Io(io::Error),
+  Failed(String),
+  TimedOut,
+}
+
+impl fmt::Display for ModuleError {
+  fn fmt(
+    &self,
+    f: &mut fmt::Formatter<'_>,
+  ) -> fmt::Result {
+    match self {
+      ModuleError::Io(err) => write!(f, "{}", err),
+      ModuleError::Failed(message) => write!(f, "{}", message),
+      ModuleError::TimedOut => write!(f, "timed out"),
+    }
+  }
+}
+
+impl From<io::Error> for ModuleError {
+  fn from(err: io::Error) -> Self {
+    ModuleError::Io(err)
+  }
+}
+
+pub trait Module: Send + Sync {
+  // Name used in the config file and for ordering output
+  fn name(&self) -> &'static str;
+
+  // Single-letter command line flag enabling the module
+  fn flag(&self) -> Option<char>;
+
+  fn category(&self) -> Category;
+
+  // One-line summary shown in --help
+  fn description(&self) -> &'static str;
+
+  // Logos are placed in the left column of the side-by-side layout
+  fn is_logo(&self) -> bool {
+    false
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError>;
+}
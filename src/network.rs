@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::Serialize;
+
+use crate::glob::matches_any;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::units::format_bytes_binary;
+
+pub struct Network;
+
+const NET_CLASS_DIR: &str = "/sys/class/net";
+
+#[derive(Serialize)]
+struct NetworkData {
+  interfaces: Vec<Interface>,
+}
+
+#[derive(Serialize, Default)]
+struct Interface {
+  name: String,
+  // operstate from sysfs ("up", "down", "dormant", ...), derived from the flags when unreadable
+  state: String,
+  // Administratively up (IFF_UP)
+  up: bool,
+  loopback: bool,
+  // Not backed by a device (bridges, veth pairs, tunnels)
+  #[serde(rename = "virtual")]
+  is_virtual: bool,
+  addresses: Vec<Address>,
+  rx_bytes: Option<u64>,
+  tx_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Address {
+  address: IpAddr,
+  prefix_len: u32,
+}
+
+impl Module for Network {
+  fn name(&self) -> &'static str {
+    "network"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('n')
+  }
+
+  fn category(&self) -> Category {
+    Category::Network
+  }
+
+  fn description(&self) -> &'static str {
+    "Network interfaces, addresses and traffic"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.network] table)
+  let show_loopback: bool = options.get("show_loopback").unwrap_or(false);
+  let show_virtual: bool = options.get("show_virtual").unwrap_or(false);
+  let show_down: bool = options.get("show_down").unwrap_or(false);
+  let show_ipv6: bool = options.get("show_ipv6").unwrap_or(true);
+  let show_link_local: bool = options.get("show_link_local").unwrap_or(false);
+  let include_interfaces: Vec<String> = options.get("include_interfaces").unwrap_or_default();
+  let exclude_interfaces: Vec<String> = options.get("exclude_interfaces").unwrap_or_default();
+
+  let mut interfaces = read_interfaces()
+    .ok_or_else(|| ModuleError::Failed("Error reading network interfaces".to_string()))?;
+  let traffic = read_traffic();
+
+  interfaces.retain(|name, interface| {
+    (show_loopback || !interface.loopback)
+      && (show_virtual || !interface.is_virtual)
+      && (show_down || interface.up)
+      && (include_interfaces.is_empty() || matches_any(&include_interfaces, name))
+      && !matches_any(&exclude_interfaces, name)
+  });
+
+  let theme = style::theme();
+  let mut output = String::new();
+  output.push_str(&format!("\n{}Network:{}\n", theme.bold, theme.reset));
+
+  let mut data = NetworkData {
+    interfaces: Vec::new(),
+  };
+
+  for (name, mut interface) in interfaces {
+    interface.addresses.retain(|address| match address.address {
+      IpAddr::V4(_) => true,
+      IpAddr::V6(ipv6) => show_ipv6 && (show_link_local || !is_link_local(&ipv6)),
+    });
+    if let Some(&(rx, tx)) = traffic.get(&name) {
+      interface.rx_bytes = Some(rx);
+      interface.tx_bytes = Some(tx);
+    }
+
+    let state_color = match interface.state.as_str() {
+      "up" => &theme.ok,
+      "down" | "lowerlayerdown" => &theme.critical,
+      _ => &theme.warn,
+    };
+    let addresses: Vec<String> = interface
+      .addresses
+      .iter()
+      .map(|address| format!("{}/{}", address.address, address.prefix_len))
+      .collect();
+
+    output.push_str(&format!(
+      "  {}{:<12}{}{:<6}{} {}{}{}\n",
+      theme.label,
+      name,
+      state_color,
+      interface.state,
+      theme.reset,
+      theme.accent,
+      addresses.join(", "),
+      theme.reset
+    ));
+    if let (Some(rx), Some(tx)) = (interface.rx_bytes, interface.tx_bytes) {
+      output.push_str(&format!(
+        "  {:<18} rx {}, tx {}\n",
+        "",
+        format_bytes_binary(rx),
+        format_bytes_binary(tx)
+      ));
+    }
+
+    data.interfaces.push(interface);
+  }
+
+  Ok(Output::new(output, &data))
+}
+
+// Every interface with its addresses, keyed and sorted by name
+fn read_interfaces() -> Option<BTreeMap<String, Interface>> {
+  let mut interfaces: BTreeMap<String, Interface> = BTreeMap::new();
+
+  let mut addrs: *mut libc::ifaddrs = std::ptr::null_mut();
+  // SAFETY: getifaddrs fills in a linked list that stays valid until freeifaddrs
+  if unsafe { libc::getifaddrs(&mut addrs) } != 0 {
+    return None;
+  }
+
+  let mut current = addrs;
+  while !current.is_null() {
+    // SAFETY: current is a non-null node of the list returned by getifaddrs
+    let ifaddr = unsafe { &*current };
+    current = ifaddr.ifa_next;
+
+    // SAFETY: ifa_name is a NUL-terminated string owned by the list
+    let name = unsafe { CStr::from_ptr(ifaddr.ifa_name) }
+      .to_string_lossy()
+      .into_owned();
+    let flags = ifaddr.ifa_flags;
+
+    let interface = interfaces.entry(name.clone()).or_insert_with(|| Interface {
+      state: operstate(&name, flags),
+      up: flags & libc::IFF_UP as u32 != 0,
+      loopback: flags & libc::IFF_LOOPBACK as u32 != 0,
+      is_virtual: is_virtual(&name),
+      name: name.clone(),
+      ..Interface::default()
+    });
+    // SAFETY: the address and netmask point to sockaddrs of the family they declare
+    if let Some(address) = unsafe { address(ifaddr.ifa_addr, ifaddr.ifa_netmask) } {
+      interface.addresses.push(address);
+    }
+  }
+
+  // SAFETY: addrs came from a successful getifaddrs call and is not used afterwards
+  unsafe { libc::freeifaddrs(addrs) };
+
+  Some(interfaces)
+}
+
+unsafe fn address(
+  addr: *const libc::sockaddr,
+  netmask: *const libc::sockaddr,
+) -> Option<Address> {
+  if addr.is_null() {
+    return None;
+  }
+
+  match unsafe { (*addr).sa_family } as i32 {
+    libc::AF_INET => {
+      let addr = unsafe { &*(addr as *const libc::sockaddr_in) };
+      let prefix_len = if netmask.is_null() {
+        32
+      } else {
+        let netmask = unsafe { &*(netmask as *const libc::sockaddr_in) };
+        netmask.sin_addr.s_addr.count_ones()
+      };
+      Some(Address {
+        address: IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))),
+        prefix_len,
+      })
+    }
+    libc::AF_INET6 => {
+      let addr = unsafe { &*(addr as *const libc::sockaddr_in6) };
+      let prefix_len = if netmask.is_null() {
+        128
+      } else {
+        let netmask = unsafe { &*(netmask as *const libc::sockaddr_in6) };
+        netmask
+          .sin6_addr
+          .s6_addr
+          .iter()
+          .map(|byte| byte.count_ones())
+          .sum()
+      };
+      Some(Address {
+        address: IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)),
+        prefix_len,
+      })
+    }
+    // AF_PACKET entries carry link-layer statistics, not addresses
+    _ => None,
+  }
+}
+
+fn operstate(
+  name: &str,
+  flags: u32,
+) -> String {
+  let running = flags & libc::IFF_RUNNING as u32 != 0;
+  match fs::read_to_string(format!("{}/{}/operstate", NET_CLASS_DIR, name)) {
+    // Loopback and many tunnels report "unknown" even when they carry traffic
+    Ok(state) if state.trim() != "unknown" || !running => state.trim().to_string(),
+    _ if running => "up".to_string(),
+    _ => "down".to_string(),
+  }
+}
+
+// Virtual interfaces live under /sys/devices/virtual. Android may hide sysfs from apps, in
+// which case nothing is treated as virtual.
+fn is_virtual(name: &str) -> bool {
+  fs::read_link(format!("{}/{}", NET_CLASS_DIR, name))
+    .is_ok_and(|target| target.to_string_lossy().contains("/virtual/"))
+}
+
+fn is_link_local(address: &Ipv6Addr) -> bool {
+  address.segments()[0] & 0xffc0 == 0xfe80
+}
+
+// Received and transmitted bytes per interface from /proc/net/dev. After two header lines,
+// each line reads "name: rx_bytes rx_packets ... (8 receive fields) tx_bytes ..."
+fn read_traffic() -> BTreeMap<String, (u64, u64)> {
+  let Ok(dev) = fs::read_to_string("/proc/net/dev") else {
+    return BTreeMap::new();
+  };
+
+  dev
+    .lines()
+    .skip(2)
+    .filter_map(|line| {
+      let (name, counters) = line.split_once(':')?;
+      let counters: Vec<u64> = counters
+        .split_whitespace()
+        .filter_map(|counter| counter.parse().ok())
+        .collect();
+      Some((
+        name.trim().to_string(),
+        (*counters.first()?, *counters.get(8)?),
+      ))
+    })
+    .collect()
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+
+pub struct Ports;
+
+// TCP_LISTEN, and TCP_CLOSE which is how the kernel reports bound, unconnected UDP sockets
+const TCP_LISTEN: &str = "0A";
+const UDP_UNCONNECTED: &str = "07";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Protocol {
+  Tcp,
+  Tcp6,
+  Udp,
+  Udp6,
+}
+
+impl Protocol {
+  const ALL: [Protocol; 4] = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6];
+
+  fn name(self) -> &'static str {
+    match self {
+      Protocol::Tcp => "tcp",
+      Protocol::Tcp6 => "tcp6",
+      Protocol::Udp => "udp",
+      Protocol::Udp6 => "udp6",
+    }
+  }
+
+  fn listening_state(self) -> &'static str {
+    match self {
+      Protocol::Tcp | Protocol::Tcp6 => TCP_LISTEN,
+      Protocol::Udp | Protocol::Udp6 => UDP_UNCONNECTED,
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct PortsData {
+  sockets: Vec<Socket>,
+}
+
+#[derive(Serialize)]
+struct Socket {
+  protocol: Protocol,
+  address: IpAddr,
+  port: u16,
+  uid: u32,
+  // Owning process, when its /proc/[pid]/fd is readable
+  pid: Option<u32>,
+  process: Option<String>,
+  #[serde(skip)]
+  inode: u64,
+}
+
+impl Module for Ports {
+  fn name(&self) -> &'static str {
+    "ports"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('o')
+  }
+
+  fn category(&self) -> Category {
+    Category::Network
+  }
+
+  fn description(&self) -> &'static str {
+    "Listening TCP and UDP ports with their processes"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.ports] table)
+  let protocols: Vec<Protocol> = options
+    .get("protocols")
+    .unwrap_or_else(|| Protocol::ALL.to_vec());
+  let show_loopback: bool = options.get("show_loopback").unwrap_or(true);
+  let exclude_ports: Vec<u16> = options.get("exclude_ports").unwrap_or_default();
+
+  // A missing table (e.g. tcp6 with IPv6 disabled) is skipped, but not all of them
+  let mut sockets: Vec<Socket> = Vec::new();
+  let mut error = None;
+  let mut readable = false;
+  for &protocol in &protocols {
+    match read_sockets(protocol) {
+      Ok(table) => {
+        sockets.extend(table);
+        readable = true;
+      }
+      Err(err) => error = error.or(Some(err)),
+    }
+  }
+  if !readable && let Some(error) = error {
+    return Err(error);
+  }
+
+  sockets.retain(|socket| show_loopback || !socket.address.is_loopback());
+  sockets.retain(|socket| !exclude_ports.contains(&socket.port));
+  sockets.sort_by_key(|socket| (socket.protocol, socket.port, socket.address));
+  sockets.dedup_by_key(|socket| (socket.protocol, socket.address, socket.port));
+
+  if !sockets.is_empty() {
+    let owners = socket_owners();
+    for socket in &mut sockets {
+      if let Some((pid, process)) = owners.get(&socket.inode) {
+        socket.pid = Some(*pid);
+        socket.process = Some(process.clone());
+      }
+    }
+  }
+
+  let theme = style::theme();
+  let mut output = String::new();
+  output.push_str(&format!(
+    "\n{}Listening Ports:{}\n",
+    theme.bold, theme.reset
+  ));
+
+  if sockets.is_empty() {
+    output.push_str("  none\n");
+  }
+  for socket in &sockets {
+    let endpoint = match socket.address {
+      IpAddr::V4(address) => format!("{}:{}", address, socket.port),
+      IpAddr::V6(address) => format!("[{}]:{}", address, socket.port),
+    };
+    let process = match (&socket.process, socket.pid) {
+      (Some(process), Some(pid)) => format!("{} ({})", process, pid),
+      _ => format!("uid {}", socket.uid),
+    };
+    output.push_str(&format!(
+      "  {}{:<6}{}{}{:<24}{} {}\n",
+      theme.label,
+      socket.protocol.name(),
+      theme.reset,
+      theme.accent,
+      endpoint,
+      theme.reset,
+      process
+    ));
+  }
+
+  Ok(Output::new(output, &PortsData { sockets }))
+}
+
+// Listening sockets of one protocol. Each line after the header reads:
+// sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...
+fn read_sockets(protocol: Protocol) -> Result<Vec<Socket>, ModuleError> {
+  let path = format!("/proc/net/{}", protocol.name());
+  let table =
+    fs::read_to_string(&path).map_err(|_| ModuleError::Failed(format!("Cannot read {}", path)))?;
+
+  let sockets = table
+    .lines()
+    .skip(1)
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      if fields.get(3) != Some(&protocol.listening_state()) {
+        return None;
+      }
+      let (address, port) = parse_endpoint(fields.get(1)?)?;
+      // Connected UDP sockets share the state of unconnected ones but have a peer port
+      let (_, remote_port) = parse_endpoint(fields.get(2)?)?;
+      if remote_port != 0 {
+        return None;
+      }
+
+      Some(Socket {
+        protocol,
+        address,
+        port,
+        uid: fields.get(7)?.parse().ok()?,
+        pid: None,
+        process: None,
+        inode: fields.get(9)?.parse().ok()?,
+      })
+    })
+    .collect();
+  Ok(sockets)
+}
+
+// "0100007F:1F90" or a 32 digit IPv6 address. Addresses are printed as 32-bit words in host
+// byte order, the port as a plain hex number.
+fn parse_endpoint(endpoint: &str) -> Option<(IpAddr, u16)> {
+  let (address, port) = endpoint.split_once(':')?;
+  let port = u16::from_str_radix(port, 16).ok()?;
+
+  let words: Vec<[u8; 4]> = (0..address.len())
+    .step_by(8)
+    .map(|start| {
+      let word = u32::from_str_radix(address.get(start..start + 8)?, 16).ok()?;
+      Some(word.to_ne_bytes())
+    })
+    .collect::<Option<_>>()?;
+
+  let address = match words[..] {
+    [word] => IpAddr::V4(Ipv4Addr::from(word)),
+    [a, b, c, d] => {
+      let mut octets = [0u8; 16];
+      for (chunk, word) in octets.chunks_mut(4).zip([a, b, c, d]) {
+        chunk.copy_from_slice(&word);
+      }
+      IpAddr::V6(Ipv6Addr::from(octets))
+    }
+    _ => return None,
+  };
+  Some((address, port))
+}
+
+// Socket inode -> (pid, command name) for every process whose fds we may read. Processes of
+// other users are skipped silently, so their sockets only show the owning uid.
+fn socket_owners() -> HashMap<u64, (u32, String)> {
+  let mut owners = HashMap::new();
+  let Ok(entries) = fs::read_dir("/proc") else {
+    return owners;
+  };
+
+  for entry in entries.flatten() {
+    let Some(pid) = entry
+      .file_name()
+      .to_str()
+      .and_then(|pid| pid.parse::<u32>().ok())
+    else {
+      continue;
+    };
+    let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+      continue;
+    };
+
+    let mut comm = None;
+    for fd in fds.flatten() {
+      let Ok(target) = fs::read_link(fd.path()) else {
+        continue;
+      };
+      let Some(inode) = target
+        .to_str()
+        .and_then(|target| target.strip_prefix("socket:["))
+        .and_then(|target| target.strip_suffix(']'))
+        .and_then(|inode| inode.parse().ok())
+      else {
+        continue;
+      };
+
+      let name = comm.get_or_insert_with(|| {
+        fs::read_to_string(entry.path().join("comm"))
+          .map(|comm| comm.trim().to_string())
+          .unwrap_or_else(|_| pid.to_string())
+      });
+      owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+    }
+  }
+
+  owners
+}
@@ -0,0 +1,47 @@
+use crate::module::Module;
+use crate::{
+  android_logo, android_logo_small, android_temp, battery, disk_space, image_logo, logo, memory,
+  network, ports, sysinfo, termux_banner, uptime, uptime_records, wifi,
+};
+
+// Every available module. Adding a module only requires listing it here.
+static MODULES: &[&dyn Module] = &[
+  &android_logo_small::AndroidLogoSmall,
+  &android_logo::AndroidLogo,
+  &logo::Logo,
+  &image_logo::ImageLogo,
+  &termux_banner::TermuxBanner,
+  &sysinfo::SysInfo,
+  &uptime::Uptime,
+  &uptime_records::UptimeRecords,
+  &memory::Memory,
+  &android_temp::AndroidTemp,
+  &battery::Battery,
+  &disk_space::DiskSpace,
+  &network::Network,
+  &ports::Ports,
+  &wifi::Wifi,
+];
+
+// Default enabled modules
+pub const DEFAULT_MODULES: &[&str] = &[
+  "android-logo-small",
+  "sysinfo",
+  "android-temp",
+  "disk-space",
+];
+
+pub fn all() -> &'static [&'static dyn Module] {
+  MODULES
+}
+
+pub fn find(name: &str) -> Option<&'static dyn Module> {
+  MODULES.iter().copied().find(|module| module.name() == name)
+}
+
+pub fn find_by_flag(flag: char) -> Option<&'static dyn Module> {
+  MODULES
+    .iter()
+    .copied()
+    .find(|module| module.flag() == Some(flag))
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fsutil;
+
+const THERMAL_DIR: &str = "/sys/class/thermal";
+const HWMON_DIR: &str = "/sys/class/hwmon";
+
+// Readings outside this range (in millidegrees) come from disabled or unpowered sensors
+const SANE_RANGE: std::ops::RangeInclusive<i64> = -40_000..=200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+  // /sys/class/thermal/thermal_zone*, the interface Android exposes
+  Thermal,
+  // /sys/class/hwmon/hwmon*/temp*_input, used by most desktop and server drivers
+  Hwmon,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Sensor {
+  // Where the reading came from, e.g. "thermal_zone3" or "hwmon2/temp1"
+  pub source: String,
+  pub label: String,
+  pub millidegrees: i64,
+  // Thresholds reported by the driver, if any
+  pub max: Option<i64>,
+  pub critical: Option<i64>,
+}
+
+pub fn read(backend: Backend) -> Vec<Sensor> {
+  match backend {
+    Backend::Thermal => thermal_zones(),
+    Backend::Hwmon => hwmon_sensors(),
+  }
+}
+
+// Every thermal_zone* with a sane reading, in zone order. The label comes from the zone type
+// and the thresholds from its "hot" and "critical" trip points.
+fn thermal_zones() -> Vec<Sensor> {
+  let Ok(entries) = fs::read_dir(THERMAL_DIR) else {
+    return Vec::new();
+  };
+
+  let mut zones: Vec<(u32, Sensor)> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let zone = entry.file_name().to_string_lossy().into_owned();
+      let index = zone.strip_prefix("thermal_zone")?.parse().ok()?;
+      let sensor = read_zone(&entry.path(), zone)?;
+      Some((index, sensor))
+    })
+    .collect();
+
+  zones.sort_by_key(|(index, _)| *index);
+  zones.into_iter().map(|(_, sensor)| sensor).collect()
+}
+
+fn read_zone(
+  path: &Path,
+  zone: String,
+) -> Option<Sensor> {
+  let millidegrees = read_millidegrees(&path.join("temp"))?;
+
+  let label = fsutil::read_trimmed(&path.join("type")).unwrap_or_else(|| zone.clone());
+
+  let mut max = None;
+  let mut critical = None;
+  for trip in 0.. {
+    let Some(trip_type) = fsutil::read_trimmed(&path.join(format!("trip_point_{}_type", trip)))
+    else {
+      break;
+    };
+    let target = match trip_type.as_str() {
+      "hot" => &mut max,
+      "critical" => &mut critical,
+      _ => continue,
+    };
+    *target = read_millidegrees(&path.join(format!("trip_point_{}_temp", trip)));
+  }
+
+  Some(Sensor {
+    source: zone,
+    label,
+    millidegrees,
+    max,
+    critical,
+  })
+}
+
+// Every temp*_input of every hwmon device. Labels combine the driver name with the channel
+// label, e.g. "coretemp Package id 0" or "nvme Composite".
+fn hwmon_sensors() -> Vec<Sensor> {
+  let Ok(entries) = fs::read_dir(HWMON_DIR) else {
+    return Vec::new();
+  };
+
+  let mut devices: Vec<(u32, String, PathBuf)> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let device = entry.file_name().to_string_lossy().into_owned();
+      let index = device.strip_prefix("hwmon")?.parse().ok()?;
+      Some((index, device, entry.path()))
+    })
+    .collect();
+  devices.sort_by_key(|(index, _, _)| *index);
+
+  let mut sensors = Vec::new();
+  for (_, device, path) in devices {
+    // Older drivers keep their attributes in the device directory
+    let path = if path.join("name").exists() || !path.join("device/name").exists() {
+      path
+    } else {
+      path.join("device")
+    };
+    let name = fsutil::read_trimmed(&path.join("name")).unwrap_or_else(|| device.clone());
+
+    let Ok(files) = fs::read_dir(&path) else {
+      continue;
+    };
+    let mut channels: Vec<u32> = files
+      .flatten()
+      .filter_map(|file| {
+        let file_name = file.file_name().to_string_lossy().into_owned();
+        file_name
+          .strip_prefix("temp")?
+          .strip_suffix("_input")?
+          .parse()
+          .ok()
+      })
+      .collect();
+    channels.sort_unstable();
+
+    for channel in channels {
+      let attribute = |suffix: &str| path.join(format!("temp{}_{}", channel, suffix));
+
+      let Some(millidegrees) = read_millidegrees(&attribute("input")) else {
+        continue;
+      };
+
+      let label = match fsutil::read_trimmed(&attribute("label")) {
+        Some(label) => format!("{} {}", name, label),
+        None => name.clone(),
+      };
+
+      sensors.push(Sensor {
+        source: format!("{}/temp{}", device, channel),
+        label,
+        millidegrees,
+        max: read_millidegrees(&attribute("max")),
+        critical: read_millidegrees(&attribute("crit")),
+      });
+    }
+  }
+
+  sensors
+}
+
+// Readings outside the sane range count as missing
+fn read_millidegrees(path: &Path) -> Option<i64> {
+  fsutil::read_trimmed(path)?
+    .parse()
+    .ok()
+    .filter(|temp| SANE_RANGE.contains(temp))
+}
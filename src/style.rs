@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+  // Color only when stdout is a terminal and NO_COLOR is not set
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+// ANSI escape codes for each semantic style role. Every field is empty when color is disabled,
+// so modules can splice them into their output unconditionally.
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+  pub reset: String,
+  pub bold: String,
+  // Field names such as "Kernel :"
+  pub label: String,
+  // Plain values next to labels
+  pub value: String,
+  // Highlighted values such as counts and load averages
+  pub accent: String,
+  // Thresholds for temperatures, usage bars and similar readings
+  pub ok: String,
+  pub warn: String,
+  pub critical: String,
+  // Main body and details of logos and banners
+  pub logo_primary: String,
+  pub logo_secondary: String,
+}
+
+// A user-defined theme from a `[themes.<name>]` table. Roles that are left out come from
+// the theme named in `inherits` (the default theme unless set).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeSpec {
+  inherits: Option<String>,
+  label: Option<ColorSpec>,
+  value: Option<ColorSpec>,
+  accent: Option<ColorSpec>,
+  ok: Option<ColorSpec>,
+  warn: Option<ColorSpec>,
+  critical: Option<ColorSpec>,
+  logo_primary: Option<ColorSpec>,
+  logo_secondary: Option<ColorSpec>,
+}
+
+// A color written as a 256-color index (214) or a string of attributes and one color:
+// a name ("bright-cyan"), an index ("214") or truecolor hex ("#fabd2f"), e.g. "bold #fabd2f"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ColorSpec {
+  Index(u8),
+  Text(String),
+}
+
+pub const BUILTIN_THEMES: &[&str] = &["default", "solarized", "gruvbox", "monochrome"];
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+pub fn init(
+  choice: ColorChoice,
+  theme_name: &str,
+  custom_themes: &HashMap<String, ThemeSpec>,
+) {
+  let enabled = match choice {
+    ColorChoice::Always => true,
+    ColorChoice::Never => false,
+    ColorChoice::Auto => {
+      // https://no-color.org: any non-empty value disables color
+      let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+      let dumb_terminal = env::var("TERM").is_ok_and(|term| term == "dumb");
+      !no_color && !dumb_terminal && stdout_is_terminal()
+    }
+  };
+
+  let theme = if enabled {
+    resolve_theme(theme_name, custom_themes, 0).unwrap_or_else(|err| {
+      eprintln!("{}", err);
+      default_theme()
+    })
+  } else {
+    Theme::default()
+  };
+  let _ = THEME.set(theme);
+}
+
+pub fn theme() -> &'static Theme {
+  THEME.get_or_init(Theme::default)
+}
+
+pub fn stdout_is_terminal() -> bool {
+  io::stdout().is_terminal()
+}
+
+// Whether the terminal advertises 24-bit color; otherwise hex colors are mapped to the 256-color cube
+pub fn truecolor_supported() -> bool {
+  env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+}
+
+// SGR parameters selecting an RGB foreground color, downsampled when truecolor is unavailable
+pub fn rgb_params(
+  r: u8,
+  g: u8,
+  b: u8,
+) -> String {
+  if truecolor_supported() {
+    format!("38;2;{};{};{}", r, g, b)
+  } else {
+    format!("38;5;{}", rgb_to_ansi256(r, g, b))
+  }
+}
+
+// Nearest entry in the xterm 256-color palette (6x6x6 cube or grayscale ramp)
+pub fn rgb_to_ansi256(
+  r: u8,
+  g: u8,
+  b: u8,
+) -> u8 {
+  if r == g && g == b {
+    return match r {
+      0..8 => 16,
+      249.. => 231,
+      gray => 232 + ((gray as u16 - 8) * 24 / 247) as u8,
+    };
+  }
+
+  let level = |component: u8| -> u8 {
+    match component {
+      0..48 => 0,
+      48..115 => 1,
+      _ => ((component as u16 - 35) / 40) as u8,
+    }
+  };
+  16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+// The color a 256-color palette index stands for, the inverse of rgb_to_ansi256
+pub fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+  // xterm's defaults for the 16 basic colors, which terminals are free to change
+  const BASIC: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+  ];
+
+  let level = |step: u8| if step == 0 { 0 } else { 55 + 40 * step };
+  match index {
+    0..16 => BASIC[index as usize],
+    16..232 => {
+      let cube = index - 16;
+      (level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+    }
+    _ => {
+      let gray = 8 + 10 * (index - 232);
+      (gray, gray, gray)
+    }
+  }
+}
+
+fn default_theme() -> Theme {
+  Theme {
+    reset: "\x1B[0m".to_string(),
+    bold: "\x1B[1m".to_string(),
+    label: "\x1B[1;36m".to_string(),
+    value: "\x1B[0;39m".to_string(),
+    accent: "\x1B[1;32m".to_string(),
+    ok: "\x1B[1;32m".to_string(),
+    warn: "\x1B[1;33m".to_string(),
+    critical: "\x1B[1;31m".to_string(),
+    logo_primary: "\x1B[1;32m".to_string(),
+    logo_secondary: "\x1B[39m".to_string(),
+  }
+}
+
+fn builtin_spec(name: &str) -> Option<ThemeSpec> {
+  let text = |spec: &str| Some(ColorSpec::Text(spec.to_string()));
+
+  let spec = match name {
+    "default" => ThemeSpec::default(),
+    "solarized" => ThemeSpec {
+      label: text("bold #268bd2"),
+      value: text("default"),
+      accent: text("bold #2aa198"),
+      ok: text("bold #859900"),
+      warn: text("bold #b58900"),
+      critical: text("bold #dc322f"),
+      logo_primary: text("bold #859900"),
+      logo_secondary: text("#6c71c4"),
+      ..ThemeSpec::default()
+    },
+    "gruvbox" => ThemeSpec {
+      label: text("bold #83a598"),
+      value: text("default"),
+      accent: text("bold #8ec07c"),
+      ok: text("bold #b8bb26"),
+      warn: text("bold #fabd2f"),
+      critical: text("bold #fb4934"),
+      logo_primary: text("bold #b8bb26"),
+      logo_secondary: text("#fe8019"),
+      ..ThemeSpec::default()
+    },
+    "monochrome" => ThemeSpec {
+      label: text("bold"),
+      value: text("default"),
+      accent: text("bold"),
+      ok: text("default"),
+      warn: text("bold"),
+      critical: text("bold underline"),
+      logo_primary: text("default"),
+      logo_secondary: text("bold"),
+      ..ThemeSpec::default()
+    },
+    _ => return None,
+  };
+  Some(spec)
+}
+
+fn resolve_theme(
+  name: &str,
+  custom_themes: &HashMap<String, ThemeSpec>,
+  depth: usize,
+) -> Result<Theme, String> {
+  if depth > BUILTIN_THEMES.len() + custom_themes.len() {
+    return Err(format!("Theme {} inherits from itself", name));
+  }
+
+  let spec = custom_themes
+    .get(name)
+    .cloned()
+    .or_else(|| builtin_spec(name))
+    .ok_or_else(|| format!("Unknown theme: {}", name))?;
+
+  let mut theme = match spec.inherits.as_deref() {
+    Some(parent) => resolve_theme(parent, custom_themes, depth + 1)?,
+    None => default_theme(),
+  };
+
+  let roles = [
+    (&mut theme.label, &spec.label),
+    (&mut theme.value, &spec.value),
+    (&mut theme.accent, &spec.accent),
+    (&mut theme.ok, &spec.ok),
+    (&mut theme.warn, &spec.warn),
+    (&mut theme.critical, &spec.critical),
+    (&mut theme.logo_primary, &spec.logo_primary),
+    (&mut theme.logo_secondary, &spec.logo_secondary),
+  ];
+  for (role, color) in roles {
+    if let Some(color) = color {
+      *role = parse_color(color).map_err(|err| format!("Theme {}: {}", name, err))?;
+    }
+  }
+
+  Ok(theme)
+}
+
+// Turn a color spec into an escape sequence. It starts with a reset so attributes of the
+// previous role (such as bold labels) never leak into the next one.
+pub fn parse_color(spec: &ColorSpec) -> Result<String, String> {
+  let text = match spec {
+    ColorSpec::Index(index) => return Ok(format!("\x1B[0;38;5;{}m", index)),
+    ColorSpec::Text(text) => text,
+  };
+
+  let mut params = vec!["0".to_string()];
+  for token in text.split_whitespace() {
+    let param = match token {
+      "bold" => "1".to_string(),
+      "dim" => "2".to_string(),
+      "italic" => "3".to_string(),
+      "underline" => "4".to_string(),
+      "default" => "39".to_string(),
+      hex if hex.starts_with('#') => parse_hex(hex)?,
+      index if index.bytes().all(|b| b.is_ascii_digit()) => {
+        let index: u8 = index
+          .parse()
+          .map_err(|_| format!("color index {} is out of range", index))?;
+        format!("38;5;{}", index)
+      }
+      name => named_color(name)
+        .map(|code| code.to_string())
+        .ok_or_else(|| format!("unknown color {}", name))?,
+    };
+    params.push(param);
+  }
+
+  Ok(format!("\x1B[{}m", params.join(";")))
+}
+
+fn parse_hex(hex: &str) -> Result<String, String> {
+  let (r, g, b) = parse_rgb(hex).ok_or_else(|| format!("invalid hex color {}", hex))?;
+  Ok(rgb_params(r, g, b))
+}
+
+// "#rrggbb" as its components
+pub fn parse_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+  let digits = hex.strip_prefix('#')?;
+  let value = u32::from_str_radix(digits, 16)
+    .ok()
+    .filter(|_| digits.len() == 6)?;
+  Some(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+fn named_color(name: &str) -> Option<u8> {
+  const NAMES: &[&str] = &[
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+  ];
+
+  let (bright, base) = match name.strip_prefix("bright-") {
+    Some(base) => (true, base),
+    None => (false, name),
+  };
+  let index = NAMES.iter().position(|&color| color == base)? as u8;
+  Some(if bright { 90 + index } else { 30 + index })
+}
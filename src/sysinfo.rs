@@ -1,308 +1,478 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs;
 use std::process::Command;
 use std::thread;
+use std::time::Duration;
 
-pub fn run() -> String {
+use serde::Serialize;
+
+use crate::cache;
+use crate::logo;
+use crate::meminfo::MemInfo;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::units::format_bytes_binary;
+use crate::uptime::{self, UptimeFormat};
+
+pub struct SysInfo;
+
+// Device model, kernel and CPU count only change with an update or reboot
+const STATIC_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl Module for SysInfo {
+  fn name(&self) -> &'static str {
+    "sysinfo"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('s')
+  }
+
+  fn category(&self) -> Category {
+    Category::System
+  }
+
+  fn description(&self) -> &'static str {
+    "System information (OS, kernel, etc.)"
+  }
+
+  fn render(
+    &self,
+    _ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    Ok(run())
+  }
+}
+
+#[derive(Serialize)]
+struct SysInfoData {
+  distro: String,
+  host: String,
+  kernel: Option<String>,
+  uptime_seconds: Option<u64>,
+  load_average: Option<LoadAverage>,
+  processes: Option<Processes>,
+  cpu_count: Option<u32>,
+  memory: Option<Memory>,
+}
+
+#[derive(Serialize)]
+struct LoadAverage {
+  one: f64,
+  five: f64,
+  fifteen: f64,
+}
+
+#[derive(Serialize)]
+struct Processes {
+  user: u32,
+  root: u32,
+  total: u32,
+}
+
+#[derive(Serialize)]
+struct Memory {
+  used_bytes: u64,
+  available_bytes: u64,
+  total_bytes: u64,
+}
+
+fn run() -> Output {
   // ANSI color codes
-  const W: &str = "\x1B[0;39m";
-  const G: &str = "\x1B[1;32m";
-  const C: &str = "\x1B[1;36m";
-  const BOLD: &str = "\x1B[1m";
-  const RESET: &str = "\x1B[0m";
+  let theme = style::theme();
+  let (w, g, c) = (&theme.value, &theme.accent, &theme.label);
+  let (bold, reset) = (&theme.bold, &theme.reset);
 
   // Spawn all independent operations in parallel
   let uptime_load_handle = thread::spawn(get_uptime_and_load_combined);
   let memory_handle = thread::spawn(get_memory_info_direct);
-  let cpu_handle = thread::spawn(get_cpu_count_direct);
+  let cpu_handle =
+    thread::spawn(|| cache::cached("sysinfo", "cpu_count", STATIC_TTL, get_cpu_count_direct));
   let process_handle = thread::spawn(get_process_info);
-  let android_handle = thread::spawn(get_android_info);
-  let kernel_handle = thread::spawn(get_kernel_info);
+  let system_handle = thread::spawn(system_info);
+  let kernel_handle = thread::spawn(cached_kernel_info);
 
   // Collect results
-  let (uptime, load1, load5, load15) = uptime_load_handle.join().unwrap();
-  let (used_mem, avail_mem, total_mem) = memory_handle.join().unwrap();
-  let cpu_count = cpu_handle.join().unwrap();
-  let (user_procs, total_procs, _root_procs) = process_handle.join().unwrap();
-  let (distro, model) = android_handle.join().unwrap();
-  let kernel_info = kernel_handle.join().unwrap();
-
-  // Build and return output string
-  format!(
+  let (uptime_seconds, load_average) = uptime_load_handle.join().unwrap();
+  let (distro, host) = system_handle.join().unwrap();
+  let data = SysInfoData {
+    distro,
+    host,
+    kernel: kernel_handle.join().unwrap(),
+    uptime_seconds,
+    load_average,
+    processes: process_handle.join().unwrap(),
+    cpu_count: cpu_handle.join().unwrap(),
+    memory: memory_handle.join().unwrap(),
+  };
+
+  let na = || "N/A".to_string();
+  let distro = &data.distro;
+  let model = &data.host;
+  let kernel_info = data.kernel.clone().unwrap_or_else(na);
+  let uptime = data.uptime_seconds.map_or_else(na, |seconds| {
+    uptime::format_uptime(seconds, UptimeFormat::Long, true)
+  });
+  let (load1, load5, load15) = data.load_average.as_ref().map_or_else(
+    || (na(), na(), na()),
+    |load| {
+      (
+        format!("{:.2}", load.one),
+        format!("{:.2}", load.five),
+        format!("{:.2}", load.fifteen),
+      )
+    },
+  );
+  let (user_procs, total_procs) = data.processes.as_ref().map_or_else(
+    || (na(), na()),
+    |procs| (procs.user.to_string(), procs.total.to_string()),
+  );
+  let cpu_count = data.cpu_count.map_or_else(na, |count| count.to_string());
+  let (used_mem, avail_mem, total_mem) = data.memory.as_ref().map_or_else(
+    || (na(), na(), na()),
+    |memory| {
+      (
+        format_bytes_binary(memory.used_bytes),
+        format_bytes_binary(memory.available_bytes),
+        format_bytes_binary(memory.total_bytes),
+      )
+    },
+  );
+
+  // Build output string
+  let text = format!(
     "
-{W}{BOLD}System Info:
-{C}  Distro    : {W}{distro}
-{C}  Host      : {W}{model}
-{C}  Kernel    : {W}{kernel_info}
+{w}{bold}System Info:
+{c}  Distro    : {w}{distro}
+{c}  Host      : {w}{model}
+{c}  Kernel    : {w}{kernel_info}
 
-{C}  Uptime    : {W}{uptime}
-{C}  Load      : {G}{load1}{W} (1m), {G}{load5}{W} (5m), {G}{load15}{W} (15m)
-{C}  Processes : {G}{user_procs}{W} (user), {G}{total_procs}{W} (total)
+{c}  Uptime    : {w}{uptime}
+{c}  Load      : {g}{load1}{w} (1m), {g}{load5}{w} (5m), {g}{load15}{w} (15m)
+{c}  Processes : {g}{user_procs}{w} (user), {g}{total_procs}{w} (total)
 
-{C}  CPU       : {G}{cpu_count}{W} vCPU core(s)
-{C}  Memory    : {G}{used_mem}{W} used, {G}{avail_mem}{W} avail, {G}{total_mem}{W} total{RESET}
+{c}  CPU       : {g}{cpu_count}{w} vCPU core(s)
+{c}  Memory    : {g}{used_mem}{w} used, {g}{avail_mem}{w} avail, {g}{total_mem}{w} total{reset}
 \n"
-  )
+  );
+
+  Output::new(text, &data)
 }
 
 // Combined uptime and load average from single call
-fn get_uptime_and_load_combined() -> (String, String, String, String) {
+fn get_uptime_and_load_combined() -> (Option<u64>, Option<LoadAverage>) {
   // Get uptime seconds from /proc/uptime (more reliable than parsing uptime command)
-  let uptime = get_formatted_uptime();
-
-  // Get load averages from uptime command
-  let (load1, load5, load15) = if let Ok(output) = Command::new("uptime").output() {
-    let output = String::from_utf8_lossy(&output.stdout);
-    if let Some(loads_part) = output.split("average: ").nth(1) {
-      let loads: Vec<_> = loads_part.split(',').map(|s| s.trim()).collect();
-      if loads.len() >= 3 {
-        (
-          loads[0].to_string(),
-          loads[1].to_string(),
-          loads[2].to_string(),
-        )
-      } else {
-        ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
-      }
-    } else {
-      ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
-    }
-  } else {
-    ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
-  };
+  let uptime = uptime::get_uptime_seconds();
+
+  // Load averages from /proc/loadavg, falling back to the uptime command
+  let load_average = fs::read_to_string("/proc/loadavg")
+    .ok()
+    .and_then(|loadavg| parse_load_average(loadavg.split_whitespace()))
+    .or_else(|| {
+      let output = Command::new("uptime").output().ok()?;
+      let output = String::from_utf8_lossy(&output.stdout);
+      let loads_part = output.split("average: ").nth(1)?;
+      parse_load_average(loads_part.split(','))
+    });
 
-  (uptime, load1, load5, load15)
+  (uptime, load_average)
 }
 
-fn get_formatted_uptime() -> String {
-  // Try to read from /proc/uptime first (most accurate)
-  if let Ok(uptime_content) = std::fs::read_to_string("/proc/uptime") {
-    if let Some(uptime_str) = uptime_content.split_whitespace().next() {
-      if let Ok(uptime_seconds) = uptime_str.parse::<f64>() {
-        return format_uptime_duration(uptime_seconds as u64);
-      }
-    }
+fn parse_load_average<'a>(fields: impl Iterator<Item = &'a str>) -> Option<LoadAverage> {
+  let loads: Vec<f64> = fields
+    .take(3)
+    .filter_map(|s| s.trim().parse().ok())
+    .collect();
+  match loads[..] {
+    [one, five, fifteen] => Some(LoadAverage { one, five, fifteen }),
+    _ => None,
   }
+}
 
-  // Fallback: parse uptime command output and convert to seconds
-  if let Ok(output) = Command::new("uptime").output() {
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    if let Some(seconds) = parse_uptime_to_seconds(&output_str) {
-      return format_uptime_duration(seconds);
-    }
+fn get_memory_info_direct() -> Option<Memory> {
+  read_meminfo().or_else(get_memory_info_from_free)
+}
+
+fn read_meminfo() -> Option<Memory> {
+  let meminfo = MemInfo::read()?;
+  let total = meminfo.bytes("MemTotal")?;
+  let available = meminfo.bytes("MemAvailable")?;
+  Some(Memory {
+    used_bytes: total.saturating_sub(available),
+    available_bytes: available,
+    total_bytes: total,
+  })
+}
+
+fn get_memory_info_from_free() -> Option<Memory> {
+  let output = Command::new("free").arg("-b").output().ok()?;
+  let output = String::from_utf8_lossy(&output.stdout);
+  let line = output.lines().find(|l| l.starts_with("Mem:"))?;
+  let parts: Vec<u64> = line
+    .split_whitespace()
+    .skip(1)
+    .filter_map(|part| part.parse().ok())
+    .collect();
+
+  // Columns: total used free shared buff/cache available
+  match parts[..] {
+    [total, used, _, _, _, available, ..] => Some(Memory {
+      used_bytes: used,
+      available_bytes: available,
+      total_bytes: total,
+    }),
+    _ => None,
   }
+}
 
-  "N/A".to_string()
+fn get_cpu_count_direct() -> Option<u32> {
+  fs::read_to_string("/sys/devices/system/cpu/present")
+    .ok()
+    .and_then(|present| parse_cpu_list(present.trim()))
+    .or_else(get_cpu_count_from_command)
 }
 
-fn parse_uptime_to_seconds(uptime_output: &str) -> Option<u64> {
-  // Parse formats like:
-  // " 12:34:56 up 2 days,  4:17,  1 user,  load average: ..."
-  // " 12:34:56 up  4:17,  1 user,  load average: ..."
-  // " 12:34:56 up 23 min,  1 user,  load average: ..."
-
-  if let Some(up_part) = uptime_output.split(" up ").nth(1) {
-    if let Some(time_part) = up_part.split(",  load average:").nth(0) {
-      // Remove user count part
-      let time_clean = time_part.split(",").collect::<Vec<_>>();
-      let time_parts = if time_clean.len() > 1 && time_clean.last().unwrap().contains("user") {
-        &time_clean[..time_clean.len() - 1]
-      } else {
-        &time_clean
-      };
+// Count CPUs in a kernel cpu list such as "0-3,6,8-9". Malformed or reversed ranges are skipped.
+fn parse_cpu_list(list: &str) -> Option<u32> {
+  let count: u32 = list
+    .split(',')
+    .filter_map(|range| match range.split_once('-') {
+      Some((start, end)) => end
+        .parse::<u32>()
+        .ok()?
+        .checked_sub(start.parse().ok()?)
+        .map(|span| span + 1),
+      None => range.parse::<u32>().ok().map(|_| 1),
+    })
+    .sum();
+  (count > 0).then_some(count)
+}
 
-      let mut total_seconds = 0u64;
-
-      for part in time_parts {
-        let part = part.trim();
-
-        if part.contains(" day") {
-          if let Some(days_str) = part.split(" day").next() {
-            if let Ok(days) = days_str.trim().parse::<u64>() {
-              total_seconds += days * 24 * 60 * 60;
-            }
-          }
-        } else if part.contains(" min") {
-          if let Some(mins_str) = part.split(" min").next() {
-            if let Ok(mins) = mins_str.trim().parse::<u64>() {
-              total_seconds += mins * 60;
-            }
-          }
-        } else if part.contains(":") {
-          // Format like "4:17" (hours:minutes)
-          let time_parts: Vec<&str> = part.split(':').collect();
-          if time_parts.len() == 2 {
-            if let (Ok(hours), Ok(minutes)) = (
-              time_parts[0].trim().parse::<u64>(),
-              time_parts[1].trim().parse::<u64>(),
-            ) {
-              total_seconds += hours * 60 * 60 + minutes * 60;
-            }
-          }
-        }
-      }
+fn get_cpu_count_from_command() -> Option<u32> {
+  Command::new("nproc")
+    .arg("--all")
+    .output()
+    .or_else(|_| {
+      Command::new("grep")
+        .args(["-c", "^processor", "/proc/cpuinfo"])
+        .output()
+    })
+    .ok()
+    .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+}
+
+fn get_process_info() -> Option<Processes> {
+  read_process_owners().or_else(get_process_info_from_ps)
+}
 
-      return Some(total_seconds);
+// Classify every visible process as root or user by the real uid in /proc/[pid]/status
+fn read_process_owners() -> Option<Processes> {
+  let mut user = 0;
+  let mut root = 0;
+
+  for entry in fs::read_dir("/proc").ok()?.flatten() {
+    let file_name = entry.file_name();
+    if !file_name
+      .to_string_lossy()
+      .bytes()
+      .all(|b| b.is_ascii_digit())
+    {
+      continue;
+    }
+
+    // Processes can exit between listing and reading
+    let Ok(status) = fs::read_to_string(entry.path().join("status")) else {
+      continue;
+    };
+
+    let uid = status
+      .lines()
+      .find_map(|line| line.strip_prefix("Uid:"))
+      .and_then(|uids| uids.split_whitespace().next());
+
+    match uid {
+      Some("0") => root += 1,
+      Some(_) => user += 1,
+      None => {}
     }
   }
 
-  None
+  (user + root > 0).then_some(Processes {
+    user,
+    root,
+    total: user + root,
+  })
 }
 
-fn format_uptime_duration(total_seconds: u64) -> String {
-  const MINUTE: u64 = 60;
-  const HOUR: u64 = MINUTE * 60;
-  const DAY: u64 = HOUR * 24;
-  const WEEK: u64 = DAY * 7;
-  const MONTH: u64 = DAY * 30; // Approximate
-
-  let mut remaining = total_seconds;
-  let mut parts = Vec::new();
-
-  if remaining >= MONTH {
-    let months = remaining / MONTH;
-    remaining %= MONTH;
-    parts.push(format!(
-      "{} month{}",
-      months,
-      if months == 1 { "" } else { "s" }
-    ));
+fn get_process_info_from_ps() -> Option<Processes> {
+  let output = Command::new("ps").arg("-eo").arg("user=").output().ok()?;
+  let output = String::from_utf8_lossy(&output.stdout);
+  let mut user = 0;
+  let mut root = 0;
+  for line in output.lines() {
+    if line == "root" {
+      root += 1;
+    } else if !line.is_empty() {
+      user += 1;
+    }
   }
+  Some(Processes {
+    user,
+    root,
+    total: user + root,
+  })
+}
 
-  if remaining >= WEEK {
-    let weeks = remaining / WEEK;
-    remaining %= WEEK;
-    parts.push(format!(
-      "{} week{}",
-      weeks,
-      if weeks == 1 { "" } else { "s" }
-    ));
+// Distro name and device model, also used by the banner's {model} placeholder
+pub fn system_info() -> (String, String) {
+  if logo::is_android() {
+    return android_info();
   }
 
-  if remaining >= DAY {
-    let days = remaining / DAY;
-    remaining %= DAY;
-    parts.push(format!("{} day{}", days, if days == 1 { "" } else { "s" }));
-  }
+  let distro = logo::os_release_name().unwrap_or_else(|| "Linux".to_string());
+  let host = hardware_model()
+    .or_else(hostname)
+    .unwrap_or_else(|| "Unknown Device".to_string());
+  (distro, host)
+}
 
-  if remaining >= HOUR {
-    let hours = remaining / HOUR;
-    remaining %= HOUR;
-    parts.push(format!(
-      "{} hour{}",
-      hours,
-      if hours == 1 { "" } else { "s" }
-    ));
-  }
+// Firmware model of a PC ("LENOVO 20XW") or a board ("Raspberry Pi 4 Model B Rev 1.4")
+fn hardware_model() -> Option<String> {
+  let read = |path: &str| {
+    fs::read_to_string(path)
+      .ok()
+      .map(|value| value.trim_matches(['\0', '\n', ' ']).to_string())
+      .filter(|value| !value.is_empty())
+  };
 
-  if remaining >= MINUTE {
-    let minutes = remaining / MINUTE;
-    parts.push(format!(
-      "{} minute{}",
-      minutes,
-      if minutes == 1 { "" } else { "s" }
-    ));
+  if let Some(product) = read("/sys/devices/virtual/dmi/id/product_name") {
+    return Some(match read("/sys/devices/virtual/dmi/id/sys_vendor") {
+      Some(vendor) if !product.starts_with(&vendor) => format!("{} {}", vendor, product),
+      _ => product,
+    });
   }
+  read("/sys/firmware/devicetree/base/model")
+}
 
-  if parts.is_empty() {
-    "less than a minute".to_string()
-  } else {
-    format!("up {}", parts.join(", "))
-  }
+// Android version and device name
+fn android_info() -> (String, String) {
+  cache::cached("sysinfo", "android", STATIC_TTL, get_android_info)
+    .unwrap_or_else(|| ("Android".to_string(), "Unknown Device".to_string()))
 }
 
-fn get_memory_info_direct() -> (String, String, String) {
-  if let Ok(output) = Command::new("free").arg("-htm").output() {
-    let output = String::from_utf8_lossy(&output.stdout);
-    if let Some(line) = output.lines().find(|l| l.starts_with("Mem:")) {
-      let parts: Vec<_> = line.split_whitespace().collect();
-      if parts.len() >= 7 {
-        return (
-          parts[2].to_string(),
-          parts[6].to_string(),
-          parts[1].to_string(),
-        );
-      }
-    }
+// None when no property could be read, so the failure is not cached
+fn get_android_info() -> Option<(String, String)> {
+  let (version, brand, model) = read_build_props().unwrap_or_else(get_android_props_from_getprop);
+  if version.is_none() && brand.is_none() && model.is_none() {
+    return None;
   }
-  ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
-}
 
-fn get_cpu_count_direct() -> String {
-  Command::new("nproc")
-    .arg("--all")
-    .output()
-    .or_else(|_| {
-      Command::new("grep")
-        .args(["-c", "^processor", "/proc/cpuinfo"])
-        .output()
-    })
-    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-    .unwrap_or_else(|_| "N/A".to_string())
+  let android_version = version
+    .map(|v| format!("Android {}", v))
+    .unwrap_or_else(|| "Android".to_string());
+
+  let device = match (brand, model) {
+    (Some(b), Some(m)) => format!("{} {}", b, m),
+    (Some(b), None) => b,
+    (None, Some(m)) => m,
+    (None, None) => "Unknown Device".to_string(),
+  };
+
+  Some((android_version, device))
 }
 
-fn get_process_info() -> (String, String, String) {
-  match Command::new("ps").arg("-eo").arg("user=").output() {
-    Ok(output) => {
-      let output = String::from_utf8_lossy(&output.stdout);
-      let mut user = 0;
-      let mut root = 0;
-      for line in output.lines() {
-        if line == "root" {
-          root += 1;
-        } else if !line.is_empty() {
-          user += 1;
-        }
+type AndroidProps = (Option<String>, Option<String>, Option<String>);
+
+// build.prop files in the order of ro.product.property_source_order, which decides where
+// ro.product.brand and ro.product.model come from
+const BUILD_PROPS: &[&str] = &[
+  "/product/etc/build.prop",
+  "/odm/etc/build.prop",
+  "/vendor/build.prop",
+  "/system_ext/etc/build.prop",
+  "/system/build.prop",
+];
+
+// Partitions built for the device. system and system_ext may be a generic system image with
+// placeholder values such as ro.product.system.model=mainline.
+const DEVICE_PARTITIONS: &[&str] = &["product", "odm", "vendor"];
+const GENERIC_PARTITIONS: &[&str] = &["system_ext", "system"];
+
+// Read the properties straight from the build.prop files getprop is populated from
+fn read_build_props() -> Option<AndroidProps> {
+  let mut props: HashMap<String, String> = HashMap::new();
+  for path in BUILD_PROPS {
+    let Ok(contents) = fs::read_to_string(path) else {
+      continue;
+    };
+    for line in contents.lines() {
+      let Some((key, value)) = line.split_once('=') else {
+        continue;
+      };
+      let value = value.trim();
+      if !value.is_empty() {
+        props
+          .entry(key.trim().to_string())
+          .or_insert_with(|| value.to_string());
       }
-      (
-        user.to_string(),
-        (user + root).to_string(),
-        root.to_string(),
-      )
     }
-    Err(_) => ("N/A".to_string(), "N/A".to_string(), "N/A".to_string()),
   }
-}
-
-fn get_android_info() -> (String, String) {
-  // Get all properties in a single getprop call (no arguments = dump all properties)
-  match Command::new("getprop").output() {
-    Ok(output) => {
-      let output_str = String::from_utf8_lossy(&output.stdout);
-
-      let mut version = None;
-      let mut brand = None;
-      let mut model = None;
-
-      // Parse the output looking for our specific properties
-      for line in output_str.lines() {
-        if let Some(value) = extract_prop_value(line, "ro.build.version.release") {
-          version = Some(value);
-        } else if let Some(value) = extract_prop_value(line, "ro.product.brand") {
-          brand = Some(value);
-        } else if let Some(value) = extract_prop_value(line, "ro.product.model") {
-          model = Some(value);
-        }
 
-        // Early exit if we have all values
-        if version.is_some() && brand.is_some() && model.is_some() {
-          break;
-        }
-      }
+  // The first partition that sets ro.product.<partition>.<name> wins, as in Android itself.
+  // Older releases only have the plain ro.product.<name>.
+  let product_prop = |name: &str, partitions: &[&str]| {
+    partitions
+      .iter()
+      .find_map(|partition| props.get(&format!("ro.product.{}.{}", partition, name)))
+      .or_else(|| props.get(&format!("ro.product.{}", name)))
+      .cloned()
+  };
 
-      let android_version = version
-        .map(|v| format!("Android {}", v))
-        .unwrap_or_else(|| "Android".to_string());
+  let version = props
+    .get("ro.build.version.release")
+    .or_else(|| props.get("ro.system.build.version.release"))
+    .cloned();
+  let model = product_prop("model", DEVICE_PARTITIONS);
+  let brand =
+    product_prop("brand", DEVICE_PARTITIONS).or_else(|| product_prop("brand", GENERIC_PARTITIONS));
+
+  // Without a device-specific model (unreadable files, or only generic system values) getprop
+  // knows better
+  if version.is_none() || model.is_none() {
+    return None;
+  }
+  Some((version, brand, model))
+}
 
-      let device = match (brand, model) {
-        (Some(b), Some(m)) => format!("{} {}", b, m),
-        (Some(b), None) => b,
-        (None, Some(m)) => m,
-        (None, None) => "Unknown Device".to_string(),
-      };
+fn get_android_props_from_getprop() -> AndroidProps {
+  // Get all properties in a single getprop call (no arguments = dump all properties)
+  let Ok(output) = Command::new("getprop").output() else {
+    return (None, None, None);
+  };
+  let output_str = String::from_utf8_lossy(&output.stdout);
+
+  let mut version = None;
+  let mut brand = None;
+  let mut model = None;
+
+  // Parse the output looking for our specific properties
+  for line in output_str.lines() {
+    if let Some(value) = extract_prop_value(line, "ro.build.version.release") {
+      version = Some(value);
+    } else if let Some(value) = extract_prop_value(line, "ro.product.brand") {
+      brand = Some(value);
+    } else if let Some(value) = extract_prop_value(line, "ro.product.model") {
+      model = Some(value);
+    }
 
-      (android_version, device)
+    // Early exit if we have all values
+    if version.is_some() && brand.is_some() && model.is_some() {
+      break;
     }
-    Err(_) => ("Android".to_string(), "Unknown Device".to_string()),
   }
+
+  (version, brand, model)
 }
 
 // Helper function to extract property value from getprop output line
@@ -326,10 +496,57 @@ fn extract_prop_value(
   None
 }
 
-fn get_kernel_info() -> String {
-  Command::new("uname")
-    .args(["-sr"])
-    .output()
-    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-    .unwrap_or_else(|_| "N/A".to_string())
+// A new kernel only runs after a reboot, so the entry is keyed by the boot it was read in
+fn cached_kernel_info() -> Option<String> {
+  match uptime::boot_id() {
+    Some(boot_id) => cache::cached(
+      "sysinfo",
+      &format!("kernel-{}", boot_id),
+      STATIC_TTL,
+      get_kernel_info,
+    ),
+    None => get_kernel_info(),
+  }
+}
+
+fn get_kernel_info() -> Option<String> {
+  uname().or_else(|| {
+    Command::new("uname")
+      .args(["-sr"])
+      .output()
+      .ok()
+      .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+  })
+}
+
+// Kernel name and release straight from uname(2), equivalent to `uname -sr`
+fn uname() -> Option<String> {
+  let uts = utsname()?;
+  Some(format!(
+    "{} {}",
+    utsname_field(&uts.sysname),
+    utsname_field(&uts.release)
+  ))
+}
+
+// Network node name, equivalent to `uname -n`
+pub fn hostname() -> Option<String> {
+  let uts = utsname()?;
+  Some(utsname_field(&uts.nodename)).filter(|name| !name.is_empty())
+}
+
+fn utsname() -> Option<libc::utsname> {
+  // SAFETY: utsname is plain old data and uname only writes into the struct we pass
+  let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+  if unsafe { libc::uname(&mut uts) } != 0 {
+    return None;
+  }
+  Some(uts)
+}
+
+fn utsname_field(chars: &[libc::c_char]) -> String {
+  // SAFETY: uname NUL-terminates every field it fills
+  unsafe { CStr::from_ptr(chars.as_ptr()) }
+    .to_string_lossy()
+    .into_owned()
 }
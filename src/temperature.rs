@@ -0,0 +1,193 @@
+use serde::Deserialize;
+
+use crate::config::ModuleOptions;
+use crate::glob::glob_match;
+use crate::module::Context;
+use crate::style;
+
+// Warning threshold below a sensor's critical point when the driver reports no max
+const WARN_MARGIN: i64 = 15_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum TemperatureUnit {
+  #[default]
+  #[serde(rename = "C", alias = "c", alias = "celsius")]
+  Celsius,
+  #[serde(rename = "F", alias = "f", alias = "fahrenheit")]
+  Fahrenheit,
+  #[serde(rename = "K", alias = "k", alias = "kelvin")]
+  Kelvin,
+}
+
+impl TemperatureUnit {
+  pub fn parse(unit: &str) -> Option<Self> {
+    match unit.to_lowercase().as_str() {
+      "c" | "celsius" => Some(TemperatureUnit::Celsius),
+      "f" | "fahrenheit" => Some(TemperatureUnit::Fahrenheit),
+      "k" | "kelvin" => Some(TemperatureUnit::Kelvin),
+      _ => None,
+    }
+  }
+
+  fn value_of(
+    self,
+    millidegrees: i64,
+  ) -> f64 {
+    let celsius = millidegrees as f64 / 1000.0;
+    match self {
+      TemperatureUnit::Celsius => celsius,
+      TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+      TemperatureUnit::Kelvin => celsius + 273.15,
+    }
+  }
+
+  fn millidegrees_of(
+    self,
+    value: f64,
+  ) -> i64 {
+    let celsius = match self {
+      TemperatureUnit::Celsius => value,
+      TemperatureUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+      TemperatureUnit::Kelvin => value - 273.15,
+    };
+    (celsius * 1000.0).round() as i64
+  }
+
+  fn symbol(self) -> &'static str {
+    match self {
+      TemperatureUnit::Celsius => "°C",
+      TemperatureUnit::Fahrenheit => "°F",
+      TemperatureUnit::Kelvin => "K",
+    }
+  }
+}
+
+// A module's own `unit` option wins over the global `temperature_unit`
+pub fn unit(ctx: &Context) -> TemperatureUnit {
+  ctx.options.get("unit").unwrap_or(ctx.temperature_unit)
+}
+
+// Readings at or above `warn` / `critical` (in millidegrees Celsius) are colored accordingly
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+  pub warn: i64,
+  pub critical: i64,
+}
+
+impl Thresholds {
+  pub const fn celsius(
+    warn: i64,
+    critical: i64,
+  ) -> Self {
+    Thresholds {
+      warn: warn * 1000,
+      critical: critical * 1000,
+    }
+  }
+
+  // Thresholds reported by a sensor driver, when it reports any
+  pub fn from_driver(
+    max: Option<i64>,
+    critical: Option<i64>,
+  ) -> Option<Self> {
+    let (warn, critical) = match (max, critical) {
+      (Some(max), Some(critical)) if max < critical => (max, critical),
+      (_, Some(critical)) => (critical - WARN_MARGIN, critical),
+      (Some(max), None) => (max, max + WARN_MARGIN),
+      (None, None) => return None,
+    };
+    Some(Thresholds { warn, critical })
+  }
+}
+
+// `warn`/`critical` values from the config, in the configured unit
+#[derive(Debug, Clone, Copy, Default)]
+struct ThresholdSpec {
+  warn: Option<f64>,
+  critical: Option<f64>,
+}
+
+impl ThresholdSpec {
+  fn apply(
+    self,
+    thresholds: Thresholds,
+    unit: TemperatureUnit,
+  ) -> Thresholds {
+    Thresholds {
+      warn: self
+        .warn
+        .map_or(thresholds.warn, |warn| unit.millidegrees_of(warn)),
+      critical: self.critical.map_or(thresholds.critical, |critical| {
+        unit.millidegrees_of(critical)
+      }),
+    }
+  }
+}
+
+// One entry of a `thresholds` list: overrides for the sensors whose names match a glob
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SensorThresholds {
+  #[serde(rename = "match")]
+  pattern: String,
+  warn: Option<f64>,
+  critical: Option<f64>,
+}
+
+// Resolve the thresholds for one sensor, starting from the ones its driver reports (or the
+// module's defaults) and overriding them with the module-wide `warn`/`critical` options and
+// then the first `thresholds` entry (in file order) whose glob matches one of the sensor's names
+pub fn thresholds_for(
+  options: &ModuleOptions,
+  unit: TemperatureUnit,
+  names: &[&str],
+  driver: Option<Thresholds>,
+  defaults: Thresholds,
+) -> Thresholds {
+  let module_wide = ThresholdSpec {
+    warn: options.get("warn"),
+    critical: options.get("critical"),
+  };
+  let thresholds = module_wide.apply(driver.unwrap_or(defaults), unit);
+
+  let per_sensor: Vec<SensorThresholds> = options.get("thresholds").unwrap_or_default();
+  per_sensor
+    .iter()
+    .find(|entry| names.iter().any(|name| glob_match(&entry.pattern, name)))
+    .map_or(thresholds, |entry| {
+      let spec = ThresholdSpec {
+        warn: entry.warn,
+        critical: entry.critical,
+      };
+      spec.apply(thresholds, unit)
+    })
+}
+
+pub fn format_temperature(
+  millidegrees: i64,
+  unit: TemperatureUnit,
+) -> String {
+  format!("{:.1}{}", unit.value_of(millidegrees), unit.symbol())
+}
+
+pub fn colored_temperature(
+  millidegrees: i64,
+  thresholds: Thresholds,
+  unit: TemperatureUnit,
+) -> String {
+  let theme = style::theme();
+  let color = if millidegrees >= thresholds.critical {
+    &theme.critical
+  } else if millidegrees >= thresholds.warn {
+    &theme.warn
+  } else {
+    &theme.ok
+  };
+
+  format!(
+    "{}{}{}",
+    color,
+    format_temperature(millidegrees, unit),
+    theme.reset
+  )
+}
@@ -1,29 +1,234 @@
-use std::process::Command;
-
-pub fn run() -> String {
-  // Check if figlet exists by trying to run it
-  if !Command::new("figlet")
-    .arg("--version")
-    .status()
-    .is_ok_and(|s| s.success())
-  {
-    return "figlet not found.\nPlease install figlet first".to_string();
-  }
-
-  // Define green color code
-  const GREEN: &str = "\x1B[1;32m";
-  const RESET: &str = "\x1B[0m";
-
-  // Execute figlet and capture output
-  match Command::new("figlet").arg("Termux").output() {
-    Ok(output) => {
-      if output.status.success() {
-        let figlet_text = String::from_utf8_lossy(&output.stdout);
-        format!("{}{}{}", GREEN, figlet_text, RESET)
-      } else {
-        "Error generating Termux banner".to_string()
+use std::env;
+use std::f64::consts::PI;
+use std::ffi::CStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::figlet::{self, Font};
+use crate::layout;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::sysinfo;
+
+pub struct TermuxBanner;
+
+const DEFAULT_TEXT: &str = "Termux";
+const DEFAULT_GRADIENT: [&str; 2] = ["#00e676", "#2979ff"];
+
+// Hue change per row of the rainbow, as in lolcat
+const RAINBOW_FREQUENCY: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ColorMode {
+  // The theme's logo color
+  #[default]
+  Solid,
+  // The gradient colors from left to right
+  Horizontal,
+  // The gradient colors from top to bottom
+  Vertical,
+  // Diagonal lolcat-style rainbow
+  Rainbow,
+}
+
+#[derive(Serialize)]
+struct Banner {
+  text: String,
+  font: String,
+  color: ColorMode,
+}
+
+impl Module for TermuxBanner {
+  fn name(&self) -> &'static str {
+    "termux-banner"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('b')
+  }
+
+  fn category(&self) -> Category {
+    Category::Branding
+  }
+
+  fn description(&self) -> &'static str {
+    "Banner text in a FIGlet font"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.termux-banner] table)
+  let text: String = options
+    .get("text")
+    .unwrap_or_else(|| DEFAULT_TEXT.to_string());
+  // A built-in font name, the name of an installed figlet font or the path of a .flf file
+  let font_name: String = options
+    .get("font")
+    .unwrap_or_else(|| figlet::DEFAULT_FONT.to_string());
+  let color: ColorMode = options.get("color").unwrap_or_default();
+  let gradient: Vec<String> = options
+    .get("gradient")
+    .unwrap_or_else(|| DEFAULT_GRADIENT.map(str::to_string).to_vec());
+  let rainbow_spread: f64 = options.get("rainbow_spread").unwrap_or(3.0);
+
+  let banner = Banner {
+    text: expand_placeholders(&text),
+    font: font_name,
+    color,
+  };
+
+  let font = Font::find(&banner.font).map_err(ModuleError::Failed)?;
+  let figlet_text = font.render(&banner.text, layout::terminal_width());
+
+  let theme = style::theme();
+  // Colors are off: no theme escapes, so no gradient either
+  if theme.reset.is_empty() {
+    return Ok(Output::new(figlet_text, &banner));
+  }
+
+  let output = match color {
+    ColorMode::Solid => format!("{}{}{}", theme.logo_primary, figlet_text, theme.reset),
+    ColorMode::Horizontal | ColorMode::Vertical => {
+      let stops = gradient
+        .iter()
+        .map(|hex| {
+          style::parse_rgb(hex)
+            .ok_or_else(|| ModuleError::Failed(format!("Invalid gradient color {}", hex)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      if stops.is_empty() {
+        return Err(ModuleError::Failed("Empty gradient".to_string()));
+      }
+      paint(&figlet_text, |x, y, width, height| {
+        let (position, length) = if color == ColorMode::Horizontal {
+          (x, width)
+        } else {
+          (y, height)
+        };
+        interpolate(
+          &stops,
+          position as f64 / length.saturating_sub(1).max(1) as f64,
+        )
+      })
+    }
+    ColorMode::Rainbow => paint(&figlet_text, |x, y, _, _| {
+      rainbow(RAINBOW_FREQUENCY * (y as f64 + x as f64 / rainbow_spread.max(0.1)))
+    }),
+  };
+
+  Ok(Output::new(output, &banner))
+}
+
+// {hostname}, {user} and {model} in the banner text. Each is only looked up when used.
+fn expand_placeholders(text: &str) -> String {
+  let mut text = text.to_string();
+  for placeholder in ["{hostname}", "{user}", "{model}"] {
+    if !text.contains(placeholder) {
+      continue;
+    }
+    let value = match placeholder {
+      "{hostname}" => sysinfo::hostname(),
+      "{user}" => user_name(),
+      _ => Some(sysinfo::system_info().1),
+    };
+    text = text.replace(placeholder, &value.unwrap_or_default());
+  }
+  text
+}
+
+// $USER when set, otherwise the passwd entry (Termux does not always export USER)
+fn user_name() -> Option<String> {
+  if let Some(user) = env::var("USER").ok().filter(|user| !user.is_empty()) {
+    return Some(user);
+  }
+
+  // SAFETY: passwd is plain old data that getpwuid_r fills with pointers into `buffer`,
+  // which outlives every read of the entry
+  let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+  let mut buffer = vec![0 as libc::c_char; 1024];
+  let mut result = std::ptr::null_mut();
+  let status = unsafe {
+    libc::getpwuid_r(
+      libc::getuid(),
+      &mut entry,
+      buffer.as_mut_ptr(),
+      buffer.len(),
+      &mut result,
+    )
+  };
+  if status != 0 || result.is_null() || entry.pw_name.is_null() {
+    return None;
+  }
+  let name = unsafe { CStr::from_ptr(entry.pw_name) };
+  Some(name.to_string_lossy().into_owned())
+}
+
+// Color every visible character by its column and row. The escape is only repeated when the
+// color changes, which keeps 256-color output short.
+fn paint(
+  text: &str,
+  color_at: impl Fn(usize, usize, usize, usize) -> (u8, u8, u8),
+) -> String {
+  let rows: Vec<&str> = text.lines().collect();
+  let width = rows
+    .iter()
+    .map(|row| row.chars().count())
+    .max()
+    .unwrap_or(0);
+  let reset = &style::theme().reset;
+
+  let mut output = String::new();
+  for (y, row) in rows.iter().enumerate() {
+    let mut current = None;
+    for (x, character) in row.chars().enumerate() {
+      if character != ' ' {
+        let (r, g, b) = color_at(x, y, width, rows.len());
+        let params = style::rgb_params(r, g, b);
+        if current.as_ref() != Some(&params) {
+          output.push_str(&format!("\x1B[{}m", params));
+          current = Some(params);
+        }
       }
+      output.push(character);
     }
-    Err(_) => "Error running figlet command".to_string(),
+    if current.is_some() {
+      output.push_str(reset);
+    }
+    output.push('\n');
   }
+  output
+}
+
+// The color `position` (0.0 to 1.0) of the way along evenly spaced stops
+fn interpolate(
+  stops: &[(u8, u8, u8)],
+  position: f64,
+) -> (u8, u8, u8) {
+  let scaled = position.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+  let index = (scaled as usize).min(stops.len().saturating_sub(2));
+  let (from, to) = (stops[index], stops[(index + 1).min(stops.len() - 1)]);
+  let fraction = scaled - index as f64;
+
+  let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+  (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+// Three sine waves a third of a period apart sweep through the hues
+fn rainbow(phase: f64) -> (u8, u8, u8) {
+  let channel = |offset: f64| ((phase + offset).sin() * 127.0 + 128.0) as u8;
+  (
+    channel(0.0),
+    channel(2.0 * PI / 3.0),
+    channel(4.0 * PI / 3.0),
+  )
 }
@@ -0,0 +1,33 @@
+// Human readable sizes, matching the output of `free -h` (binary) and `df -H` (SI)
+
+const BINARY_SUFFIXES: &[&str] = &["B", "Ki", "Mi", "Gi", "Ti", "Pi"];
+const SI_SUFFIXES: &[&str] = &["B", "k", "M", "G", "T", "P"];
+
+pub fn format_bytes_binary(bytes: u64) -> String {
+  format_scaled(bytes, 1024.0, BINARY_SUFFIXES)
+}
+
+pub fn format_bytes_si(bytes: u64) -> String {
+  format_scaled(bytes, 1000.0, SI_SUFFIXES)
+}
+
+fn format_scaled(
+  bytes: u64,
+  base: f64,
+  suffixes: &[&str],
+) -> String {
+  let mut value = bytes as f64;
+  let mut suffix = 0;
+
+  while value >= base && suffix < suffixes.len() - 1 {
+    value /= base;
+    suffix += 1;
+  }
+
+  // One decimal for small values, like coreutils does
+  if suffix == 0 || value >= 10.0 {
+    format!("{:.0}{}", value, suffixes[suffix])
+  } else {
+    format!("{:.1}{}", value, suffixes[suffix])
+  }
+}
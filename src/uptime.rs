@@ -1,15 +1,237 @@
+use std::fs;
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn run() -> String {
-  // Execute uptime -p and handle output
-  match Command::new("uptime").arg("-p").output() {
-    Ok(output) => {
-      if output.status.success() {
-        format!("\n{}", String::from_utf8_lossy(&output.stdout))
-      } else {
-        "\nError getting uptime information".to_string()
+use serde::{Deserialize, Serialize};
+
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+
+pub struct Uptime;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UptimeFormat {
+  // "up 3 days, 4 hours, 12 minutes"
+  #[default]
+  Long,
+  // "3d 4h 12m"
+  Short,
+}
+
+#[derive(Serialize)]
+struct UptimeData {
+  seconds: u64,
+  pretty: String,
+  // Unix timestamp of the boot
+  boot_time: Option<i64>,
+}
+
+impl Module for Uptime {
+  fn name(&self) -> &'static str {
+    "uptime"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('u')
+  }
+
+  fn category(&self) -> Category {
+    Category::System
+  }
+
+  fn description(&self) -> &'static str {
+    "System uptime"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.uptime] table)
+  let format: UptimeFormat = options.get("format").unwrap_or_default();
+  // Months of 30 days and weeks, instead of counting days only
+  let months_and_weeks: bool = options.get("months_and_weeks").unwrap_or(true);
+  let show_since: bool = options.get("show_since").unwrap_or(true);
+
+  let seconds = get_uptime_seconds()
+    .ok_or_else(|| ModuleError::Failed("Error getting uptime information".to_string()))?;
+  let boot_time = boot_time(seconds);
+
+  let data = UptimeData {
+    seconds,
+    pretty: format_uptime(seconds, format, months_and_weeks),
+    boot_time,
+  };
+
+  let theme = style::theme();
+  let since = boot_time
+    .filter(|_| show_since)
+    .and_then(format_local_time)
+    .map(|since| format!(" {}(since {}){}", theme.label, since, theme.reset))
+    .unwrap_or_default();
+
+  Ok(Output::new(format!("\n{}{}\n", data.pretty, since), &data))
+}
+
+pub fn get_uptime_seconds() -> Option<u64> {
+  // Try to read from /proc/uptime first (most accurate)
+  if let Ok(uptime_content) = fs::read_to_string("/proc/uptime")
+    && let Some(uptime_str) = uptime_content.split_whitespace().next()
+    && let Ok(uptime_seconds) = uptime_str.parse::<f64>()
+  {
+    return Some(uptime_seconds as u64);
+  }
+
+  // Fallback: parse uptime command output and convert to seconds
+  let output = Command::new("uptime").output().ok()?;
+  parse_uptime_to_seconds(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_uptime_to_seconds(uptime_output: &str) -> Option<u64> {
+  // Parse formats like:
+  // " 12:34:56 up 2 days,  4:17,  1 user,  load average: ..."
+  // " 12:34:56 up  4:17,  1 user,  load average: ..."
+  // " 12:34:56 up 23 min,  1 user,  load average: ..."
+
+  if let Some(up_part) = uptime_output.split(" up ").nth(1)
+    && let Some(time_part) = up_part.split(",  load average:").next()
+  {
+    // Remove user count part
+    let time_clean = time_part.split(",").collect::<Vec<_>>();
+    let time_parts = if time_clean.len() > 1 && time_clean.last().unwrap().contains("user") {
+      &time_clean[..time_clean.len() - 1]
+    } else {
+      &time_clean
+    };
+
+    let mut total_seconds = 0u64;
+
+    for part in time_parts {
+      let part = part.trim();
+
+      if part.contains(" day") {
+        if let Some(days_str) = part.split(" day").next()
+          && let Ok(days) = days_str.trim().parse::<u64>()
+        {
+          total_seconds += days * 24 * 60 * 60;
+        }
+      } else if part.contains(" min") {
+        if let Some(mins_str) = part.split(" min").next()
+          && let Ok(mins) = mins_str.trim().parse::<u64>()
+        {
+          total_seconds += mins * 60;
+        }
+      } else if part.contains(":") {
+        // Format like "4:17" (hours:minutes)
+        let time_parts: Vec<&str> = part.split(':').collect();
+        if time_parts.len() == 2
+          && let (Ok(hours), Ok(minutes)) = (
+            time_parts[0].trim().parse::<u64>(),
+            time_parts[1].trim().parse::<u64>(),
+          )
+        {
+          total_seconds += hours * 60 * 60 + minutes * 60;
+        }
       }
     }
-    Err(_) => "\nError running uptime command".to_string(),
+
+    return Some(total_seconds);
+  }
+
+  None
+}
+
+pub fn format_uptime(
+  total_seconds: u64,
+  format: UptimeFormat,
+  months_and_weeks: bool,
+) -> String {
+  const MINUTE: u64 = 60;
+  const HOUR: u64 = MINUTE * 60;
+  const DAY: u64 = HOUR * 24;
+  const WEEK: u64 = DAY * 7;
+  const MONTH: u64 = DAY * 30; // Approximate
+
+  // (length, long name, short suffix)
+  let mut units = vec![
+    (DAY, "day", "d"),
+    (HOUR, "hour", "h"),
+    (MINUTE, "minute", "m"),
+  ];
+  if months_and_weeks {
+    units.splice(0..0, [(MONTH, "month", "mo"), (WEEK, "week", "w")]);
+  }
+
+  let mut remaining = total_seconds;
+  let mut parts = Vec::new();
+
+  for (length, name, suffix) in units {
+    if remaining < length {
+      continue;
+    }
+    let count = remaining / length;
+    remaining %= length;
+    parts.push(match format {
+      UptimeFormat::Long => format!("{} {}{}", count, name, if count == 1 { "" } else { "s" }),
+      UptimeFormat::Short => format!("{}{}", count, suffix),
+    });
   }
+
+  match format {
+    UptimeFormat::Long if parts.is_empty() => "less than a minute".to_string(),
+    UptimeFormat::Long => format!("up {}", parts.join(", ")),
+    UptimeFormat::Short if parts.is_empty() => "0m".to_string(),
+    UptimeFormat::Short => parts.join(" "),
+  }
+}
+
+// Unix timestamp of the last boot, from the btime line of /proc/stat or derived from the uptime
+pub fn boot_time(uptime_seconds: u64) -> Option<i64> {
+  fs::read_to_string("/proc/stat")
+    .ok()
+    .and_then(|stat| {
+      stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|btime| btime.trim().parse().ok())
+    })
+    .or_else(|| {
+      let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+      Some(now.checked_sub(uptime_seconds)? as i64)
+    })
+}
+
+// Random id the kernel generates at every boot
+pub fn boot_id() -> Option<String> {
+  fs::read_to_string("/proc/sys/kernel/random/boot_id")
+    .ok()
+    .map(|id| id.trim().to_string())
+    .filter(|id| !id.is_empty())
+}
+
+// "2026-10-12 08:14" in the local time zone
+pub fn format_local_time(timestamp: i64) -> Option<String> {
+  let time = timestamp as libc::time_t;
+  // SAFETY: tm is plain old data and is only read after localtime_r succeeds
+  let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+  if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+    return None;
+  }
+
+  Some(format!(
+    "{:04}-{:02}-{:02} {:02}:{:02}",
+    tm.tm_year + 1900,
+    tm.tm_mon + 1,
+    tm.tm_mday,
+    tm.tm_hour,
+    tm.tm_min
+  ))
 }
@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fsutil;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+use crate::uptime::{self, UptimeFormat};
+
+pub struct UptimeRecords;
+
+// Sessions kept in the data file, the shortest ones are dropped first
+const DEFAULT_MAX_RECORDS: usize = 100;
+
+#[derive(Default, Serialize, Deserialize)]
+struct RecordsFile {
+  sessions: Vec<Session>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Session {
+  boot_id: String,
+  // Unix timestamp of the boot
+  boot_time: Option<i64>,
+  // Longest uptime seen during this boot
+  uptime_seconds: u64,
+}
+
+#[derive(Serialize)]
+struct RecordsData {
+  current: Session,
+  // 1-based position of the current boot among all recorded sessions
+  rank: usize,
+  total: usize,
+  top: Vec<Session>,
+}
+
+impl Module for UptimeRecords {
+  fn name(&self) -> &'static str {
+    "uptime-records"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('r')
+  }
+
+  fn category(&self) -> Category {
+    Category::System
+  }
+
+  fn description(&self) -> &'static str {
+    "Longest uptimes and the current boot's rank"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.uptime-records] table)
+  let top_count: usize = options.get("top").unwrap_or(5);
+  let max_records: usize = options.get("max_records").unwrap_or(DEFAULT_MAX_RECORDS);
+  let format: UptimeFormat = options.get("format").unwrap_or(UptimeFormat::Short);
+  let months_and_weeks: bool = options.get("months_and_weeks").unwrap_or(false);
+
+  let uptime_seconds = uptime::get_uptime_seconds()
+    .ok_or_else(|| ModuleError::Failed("Error getting uptime information".to_string()))?;
+  let boot_id = uptime::boot_id()
+    .ok_or_else(|| ModuleError::Failed("Error reading the boot id".to_string()))?;
+
+  let current = Session {
+    boot_id,
+    boot_time: uptime::boot_time(uptime_seconds),
+    uptime_seconds,
+  };
+
+  let mut records = read_records();
+  match records
+    .sessions
+    .iter_mut()
+    .find(|session| session.boot_id == current.boot_id)
+  {
+    Some(session) => session.uptime_seconds = session.uptime_seconds.max(uptime_seconds),
+    None => records.sessions.push(current.clone()),
+  }
+  records
+    .sessions
+    .sort_by_key(|session| Reverse(session.uptime_seconds));
+
+  let rank = records
+    .sessions
+    .iter()
+    .position(|session| session.boot_id == current.boot_id)
+    .map_or(0, |index| index + 1);
+  let total = records.sessions.len();
+
+  records.sessions.truncate(max_records.max(1));
+  write_records(&records);
+
+  let theme = style::theme();
+  let pretty = |seconds: u64| uptime::format_uptime(seconds, format, months_and_weeks);
+
+  let mut output = String::new();
+  output.push_str(&format!("\n{}Uptime Records:{}\n", theme.bold, theme.reset));
+
+  let standing = if rank == 1 && total > 1 {
+    format!("{}a new record{}", theme.ok, theme.reset)
+  } else {
+    format!("{}#{}{} of {}", theme.accent, rank, theme.reset, total)
+  };
+  output.push_str(&format!(
+    "  Current uptime {} is {}\n",
+    pretty(uptime_seconds),
+    standing
+  ));
+
+  let top: Vec<Session> = records.sessions.iter().take(top_count).cloned().collect();
+  for (index, session) in top.iter().enumerate() {
+    let is_current = session.boot_id == current.boot_id;
+    let boot = session
+      .boot_time
+      .and_then(uptime::format_local_time)
+      .unwrap_or_default();
+    output.push_str(&format!(
+      "  {}{:>3} {}{:<16}{} {}{}{}\n",
+      if is_current { "->" } else { "  " },
+      index + 1,
+      if is_current {
+        &theme.accent
+      } else {
+        &theme.value
+      },
+      pretty(session.uptime_seconds),
+      theme.reset,
+      theme.label,
+      boot,
+      theme.reset
+    ));
+  }
+
+  let data = RecordsData {
+    current,
+    rank,
+    total,
+    top,
+  };
+  Ok(Output::new(output, &data))
+}
+
+// Records live next to other user data, not in the cache: losing them loses history
+fn records_file() -> Option<PathBuf> {
+  fsutil::xdg_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join("uptime-records.json"))
+}
+
+fn read_records() -> RecordsFile {
+  records_file()
+    .and_then(|path| fs::read(path).ok())
+    .and_then(|contents| serde_json::from_slice(&contents).ok())
+    .unwrap_or_default()
+}
+
+fn write_records(records: &RecordsFile) {
+  if let Some(path) = records_file()
+    && let Ok(contents) = serde_json::to_vec_pretty(records)
+  {
+    let _ = fsutil::atomic_write(&path, &contents);
+  }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::bar;
+use crate::module::{Category, Context, Module, ModuleError, Output};
+use crate::style;
+
+pub struct Wifi;
+
+// Link quality maximum reported by cfg80211 drivers
+const MAX_LINK_QUALITY: f64 = 70.0;
+
+#[derive(Serialize)]
+struct WifiData {
+  interfaces: Vec<WifiLink>,
+}
+
+#[derive(Serialize)]
+struct WifiLink {
+  interface: String,
+  ssid: Option<String>,
+  link_quality: f64,
+  signal_dbm: Option<i32>,
+  signal_percent: u32,
+}
+
+impl Module for Wifi {
+  fn name(&self) -> &'static str {
+    "wifi"
+  }
+
+  fn flag(&self) -> Option<char> {
+    Some('w')
+  }
+
+  fn category(&self) -> Category {
+    Category::Network
+  }
+
+  fn description(&self) -> &'static str {
+    "Wi-Fi SSID and signal strength"
+  }
+
+  fn render(
+    &self,
+    ctx: &Context,
+  ) -> Result<Output, ModuleError> {
+    run(ctx)
+  }
+}
+
+fn run(ctx: &Context) -> Result<Output, ModuleError> {
+  let options = &ctx.options;
+
+  // Configuration (overridable from the [options.wifi] table)
+  let low_signal: u32 = options.get("low_signal").unwrap_or(30);
+  let alert_signal: u32 = options.get("alert_signal").unwrap_or(50);
+  let bar_width: usize = options.get("bar_width").unwrap_or(20);
+  let show_ssid: bool = options.get("show_ssid").unwrap_or(true);
+
+  let mut links = read_wireless();
+  // Nothing to report on devices without a wireless interface
+  if links.is_empty() {
+    return Ok(Output::text(String::new()));
+  }
+
+  if show_ssid {
+    for link in &mut links {
+      link.ssid = read_ssid(&link.interface);
+    }
+  }
+
+  let theme = style::theme();
+  let mut output = String::new();
+
+  for link in &links {
+    let ssid = link.ssid.as_deref().unwrap_or("unknown network");
+    let signal = link
+      .signal_dbm
+      .map(|dbm| format!(", {} dBm", dbm))
+      .unwrap_or_default();
+    output.push_str(&format!(
+      "\n{}Wi-Fi ({}):{} {}{}{} {}%{}\n",
+      theme.bold,
+      link.interface,
+      theme.reset,
+      theme.accent,
+      ssid,
+      theme.reset,
+      link.signal_percent,
+      signal
+    ));
+
+    // Weak signal is the bad end, like low battery
+    let color = if link.signal_percent <= low_signal {
+      &theme.critical
+    } else if link.signal_percent <= alert_signal {
+      &theme.warn
+    } else {
+      &theme.ok
+    };
+    output.push_str(&format!(
+      "  {}\n",
+      bar::render(link.signal_percent, bar_width, color)
+    ));
+  }
+
+  Ok(Output::new(output, &WifiData { interfaces: links }))
+}
+
+// Interfaces listed in /proc/net/wireless. After two header lines, each reads
+// "wlan0: 0000   70.  -40.  -256  ..." with status, link quality, signal level and noise.
+fn read_wireless() -> Vec<WifiLink> {
+  let Ok(wireless) = fs::read_to_string("/proc/net/wireless") else {
+    return Vec::new();
+  };
+
+  wireless
+    .lines()
+    .skip(2)
+    .filter_map(|line| {
+      let (interface, fields) = line.split_once(':')?;
+      let mut fields = fields
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.trim_end_matches('.'));
+      let link_quality: f64 = fields.next()?.parse().ok()?;
+      // Drivers report the level in dBm as a negative number, older ones as an unsigned byte
+      let signal_dbm = fields
+        .next()
+        .and_then(|level| level.parse::<i32>().ok())
+        .map(|level| if level > 63 { level - 256 } else { level })
+        .filter(|level| *level < 0);
+
+      let signal_percent = match (link_quality, signal_dbm) {
+        (quality, _) if quality > 0.0 => (quality * 100.0 / MAX_LINK_QUALITY).min(100.0) as u32,
+        // -100 dBm is unusable, -50 dBm is as good as it gets
+        (_, Some(dbm)) => (2 * (dbm + 100)).clamp(0, 100) as u32,
+        _ => 0,
+      };
+
+      Some(WifiLink {
+        interface: interface.trim().to_string(),
+        ssid: None,
+        link_quality,
+        signal_dbm,
+        signal_percent,
+      })
+    })
+    .collect()
+}
+
+// The SSID is not part of /proc/net/wireless, so ask whichever tool is installed:
+// wireless-tools, iw, or Termux:API on unrooted Android
+fn read_ssid(interface: &str) -> Option<String> {
+  let run = |program: &str, args: &[&str]| -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+      .status
+      .success()
+      .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+  };
+
+  // iwgetid succeeds with no output when it cannot tell, so empty answers fall through too
+  let ssid = run("iwgetid", &["-r", interface])
+    .map(|ssid| ssid.trim().to_string())
+    .filter(|ssid| !ssid.is_empty())
+    .or_else(|| {
+      let link = run("iw", &["dev", interface, "link"])?;
+      link
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("SSID:"))
+        .map(|ssid| ssid.trim().to_string())
+        .filter(|ssid| !ssid.is_empty())
+    })
+    .or_else(|| {
+      let info = run("termux-wifi-connectioninfo", &[])?;
+      let info: serde_json::Value = serde_json::from_str(&info).ok()?;
+      info["ssid"].as_str().map(str::to_string)
+    })?;
+
+  // Android hides the SSID from apps without location permission
+  Some(ssid).filter(|ssid| !ssid.is_empty() && ssid != "<unknown ssid>")
+}